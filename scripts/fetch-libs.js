@@ -0,0 +1,56 @@
+#!/usr/bin/env node
+
+// Downloads the libraries listed in src/libs/libraries.json into src/libs so
+// they get bundled with the app and new games work without internet.
+// Files that are already there are not fetched again. Every file has to match
+// the sha256 pinned in libraries.json or the build fails.
+
+import { createHash } from 'crypto';
+import { readFileSync, writeFileSync, existsSync, mkdirSync } from 'fs';
+import { dirname, join } from 'path';
+
+const libsDir = './src/libs';
+const libraries = JSON.parse(readFileSync(join(libsDir, 'libraries.json'), 'utf8'));
+
+function verifyLibrary(library, contents) {
+  const actual = createHash('sha256').update(contents).digest('hex');
+  if (actual !== library.sha256) {
+    throw new Error(
+      `${library.file} does not match the sha256 in libraries.json\n` +
+      `   expected: ${library.sha256 || '(none pinned)'}\n` +
+      `   actual:   ${actual}`
+    );
+  }
+}
+
+async function fetchLibrary(library) {
+  const target = join(libsDir, library.file);
+  if (existsSync(target)) {
+    verifyLibrary(library, readFileSync(target));
+    return;
+  }
+
+  const url = library.cdn_urls[0];
+  console.log(`📦 Fetching ${library.id} ${library.version} from ${url}`);
+
+  const response = await fetch(url);
+  if (!response.ok) {
+    throw new Error(`Failed to fetch ${url}: ${response.status} ${response.statusText}`);
+  }
+
+  // Check before writing so a bad download is never bundled
+  const contents = Buffer.from(await response.arrayBuffer());
+  verifyLibrary(library, contents);
+
+  mkdirSync(dirname(target), { recursive: true });
+  writeFileSync(target, contents);
+}
+
+try {
+  for (const library of libraries) {
+    await fetchLibrary(library);
+  }
+} catch (error) {
+  console.error('❌', error.message);
+  process.exit(1);
+}
@@ -0,0 +1,423 @@
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::GroveError;
+use crate::{files, manifest, snapshots, thumbnails, validation};
+
+// Games travel between computers as zip files: one folder with the game's
+// files, its manifest and its thumbnail, but no history or caches.
+
+// Anything bigger is not a kid's game, or is a zip bomb
+pub const MAX_IMPORT_SIZE: u64 = 200 * 1024 * 1024;
+pub const MAX_IMPORT_FILES: usize = 5_000;
+
+const MACOS_METADATA_FOLDER: &str = "__MACOSX";
+
+pub fn export_game_zip(game_path: &Path, out_path: &Path) -> Result<(), GroveError> {
+    let folder_name = game_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| GroveError::not_a_directory(game_path))?;
+
+    let mut game_files = Vec::new();
+    collect_game_files(game_path, &mut game_files);
+
+    // The thumbnail lives in the hidden .grove folder the walk skips
+    if let Some(thumbnail) = thumbnails::existing_thumbnail(game_path) {
+        game_files.push(PathBuf::from(thumbnail));
+    }
+
+    let file = File::create(out_path)
+        .map_err(|e| GroveError::io("create_zip", e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for path in game_files {
+        let relative = match path.strip_prefix(game_path) {
+            Ok(relative) => relative,
+            Err(_) => continue,
+        };
+
+        // Everything sits in one folder, so unzipping by hand gives a game folder
+        let mut entry_name = folder_name.clone();
+        for component in relative.components() {
+            entry_name.push('/');
+            entry_name.push_str(&component.as_os_str().to_string_lossy());
+        }
+
+        zip.start_file(entry_name, options)
+            .map_err(|e| GroveError::io("write_zip", e))?;
+        let mut source = File::open(&path)
+            .map_err(|e| GroveError::io("read_game_file", e))?;
+        io::copy(&mut source, &mut zip)
+            .map_err(|e| GroveError::io("write_zip", e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| GroveError::io("write_zip", e))?;
+
+    Ok(())
+}
+
+// Game files without history and caches. Symlinks are left out: they can
+// loop forever or pull in files from outside the game.
+fn collect_game_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        if files::is_ignored_name(&entry.file_name()) {
+            continue;
+        }
+
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_game_files(&entry.path(), found),
+            Ok(file_type) if file_type.is_file() => found.push(entry.path()),
+            _ => {}
+        }
+    }
+}
+
+// Unpacks a game zip into a library root and returns the new game's path.
+// A game with the same name already there is kept, the import gets "Name (2)".
+pub fn import_game_zip(zip_path: &Path, library_root: &Path) -> Result<PathBuf, GroveError> {
+    let file = File::open(zip_path)
+        .map_err(|_| GroveError::not_found(zip_path))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| GroveError::InvalidArchive { details: e.to_string() })?;
+
+    if archive.len() > MAX_IMPORT_FILES {
+        return Err(GroveError::InvalidArchive {
+            details: format!("more than {} files", MAX_IMPORT_FILES),
+        });
+    }
+
+    // Check every entry before writing anything
+    let mut entries = Vec::new();
+    let mut declared_size: u64 = 0;
+    for index in 0..archive.len() {
+        let entry = archive.by_index(index)
+            .map_err(|e| GroveError::InvalidArchive { details: e.to_string() })?;
+
+        // Refuses "../../x" and absolute paths that would land outside the game
+        let path = entry.enclosed_name()
+            .ok_or_else(|| GroveError::InvalidArchive { details: format!("unsafe path: {}", entry.name()) })?;
+        if entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000) {
+            return Err(GroveError::InvalidArchive { details: format!("symbolic link: {}", entry.name()) });
+        }
+
+        // Finder adds resource forks in a folder of their own
+        if path.starts_with(MACOS_METADATA_FOLDER) {
+            continue;
+        }
+
+        declared_size = declared_size.saturating_add(entry.size());
+        if declared_size > MAX_IMPORT_SIZE {
+            return Err(GroveError::ArchiveTooLarge { max_bytes: MAX_IMPORT_SIZE });
+        }
+
+        entries.push((index, path, entry.is_dir()));
+    }
+
+    // Zips made by export_game_zip, or by zipping a folder by hand, have the
+    // game inside a single folder
+    let top_folder = single_top_folder(&entries);
+    let base_name = match &top_folder {
+        Some(folder) => folder.clone(),
+        None => zip_path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let folder_name = validation::sanitize_folder_name(&base_name)?;
+
+    // Unpacked next to the other games under a hidden name, so a half
+    // finished import never shows up in the library. Each import gets its
+    // own, two imports of the same zip can run at once.
+    let staging_path = library_root.join(format!(".importing-{}", manifest::new_game_id()));
+    fs::create_dir(&staging_path)
+        .map_err(|e| GroveError::io("create_game_folder", e))?;
+
+    let result = unpack(&mut archive, &entries, top_folder.as_deref(), &staging_path)
+        .and_then(|_| {
+            if staging_path.join("index.html").is_file() {
+                Ok(())
+            } else {
+                Err(GroveError::MissingIndexHtml { path: zip_path.to_string_lossy().to_string() })
+            }
+        })
+        .and_then(|_| {
+            let game_path = library_root.join(files::unique_folder_name(library_root, &folder_name));
+            fs::rename(&staging_path, &game_path)
+                .map_err(|e| GroveError::io("move_imported_game", e))?;
+            Ok(game_path)
+        });
+
+    let game_path = match result {
+        Ok(game_path) => game_path,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging_path);
+            return Err(e);
+        }
+    };
+
+    // Games from before manifests existed get one now. A game imported
+    // twice, or brought back to the computer it came from, is a copy and
+    // can't share the original's id.
+    let mut game_manifest = manifest::read_manifest(&game_path)
+        .unwrap_or_else(|| manifest::GameManifest::untemplated(&base_name));
+    game_manifest.id = manifest::new_game_id();
+    manifest::write_manifest(&game_path, &game_manifest)?;
+
+    // A fresh history, the zip never carries one
+    if let Err(e) = snapshots::init_repository(&game_path)
+        .and_then(|_| snapshots::take_snapshot(&game_path, "Imported the game"))
+    {
+        println!("Failed to snapshot imported game: {}", e);
+    }
+
+    Ok(game_path)
+}
+
+fn unpack(
+    archive: &mut ZipArchive<File>,
+    entries: &[(usize, PathBuf, bool)],
+    top_folder: Option<&str>,
+    target: &Path,
+) -> Result<(), GroveError> {
+    let mut written: u64 = 0;
+
+    for (index, path, is_dir) in entries {
+        let relative = match top_folder {
+            Some(top_folder) => path.strip_prefix(top_folder).unwrap_or(path),
+            None => path.as_path(),
+        };
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+
+        // History and caches from another computer don't come along, the
+        // thumbnail in the hidden .grove folder does
+        let skipped = relative != thumbnails::thumbnail_path(Path::new(""))
+            && relative.components().any(|component| match component {
+                Component::Normal(name) => files::is_ignored_name(name),
+                _ => true,
+            });
+        if skipped {
+            continue;
+        }
+
+        let out_path = target.join(relative);
+        if *is_dir {
+            fs::create_dir_all(&out_path)
+                .map_err(|e| GroveError::io("unpack_zip", e))?;
+            continue;
+        }
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| GroveError::io("unpack_zip", e))?;
+        }
+
+        let entry = archive.by_index(*index)
+            .map_err(|e| GroveError::InvalidArchive { details: e.to_string() })?;
+        let mut out_file = File::create(&out_path)
+            .map_err(|e| GroveError::io("unpack_zip", e))?;
+
+        // Sizes in the zip can lie, so count what actually comes out
+        let remaining = MAX_IMPORT_SIZE - written;
+        let copied = io::copy(&mut entry.take(remaining + 1), &mut out_file)
+            .map_err(|e| GroveError::InvalidArchive { details: e.to_string() })?;
+        written += copied;
+        if written > MAX_IMPORT_SIZE {
+            return Err(GroveError::ArchiveTooLarge { max_bytes: MAX_IMPORT_SIZE });
+        }
+    }
+
+    Ok(())
+}
+
+// The folder every entry lives in, if there is exactly one
+fn single_top_folder(entries: &[(usize, PathBuf, bool)]) -> Option<String> {
+    let mut top: Option<&OsStr> = None;
+
+    for (_, path, is_dir) in entries {
+        let mut components = path.components();
+        let first = match components.next() {
+            Some(Component::Normal(first)) => first,
+            _ => return None,
+        };
+
+        // A file at the top level means there's no wrapping folder
+        if components.next().is_none() && !is_dir {
+            return None;
+        }
+
+        match top {
+            Some(top) if top != first => return None,
+            _ => top = Some(first),
+        }
+    }
+
+    top.map(|top| top.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    // A fresh folder for each test, removed again when the test is done
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("grove-archive-test-{}", manifest::new_game_id()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_zip(zip_path: &Path, build: impl FnOnce(&mut ZipWriter<File>)) {
+        let mut zip = ZipWriter::new(File::create(zip_path).unwrap());
+        build(&mut zip);
+        zip.finish().unwrap();
+    }
+
+    fn add_file(zip: &mut ZipWriter<File>, name: &str, contents: &[u8]) {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents).unwrap();
+    }
+
+    fn entry(path: &str, is_dir: bool) -> (usize, PathBuf, bool) {
+        (0, PathBuf::from(path), is_dir)
+    }
+
+    // Only the staging folder may ever have been created, and it is gone
+    fn assert_library_empty(library: &Path) {
+        assert_eq!(fs::read_dir(library).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn imports_a_wrapped_game() {
+        let temp = TempDir::new();
+        let library = temp.0.join("library");
+        fs::create_dir(&library).unwrap();
+        let zip_path = temp.0.join("download.zip");
+        write_zip(&zip_path, |zip| {
+            add_file(zip, "space-game/index.html", b"<html></html>");
+            add_file(zip, "space-game/.cursorrules", b"rules");
+            add_file(zip, "space-game/.git/HEAD", b"ref: refs/heads/main");
+        });
+
+        let game_path = import_game_zip(&zip_path, &library).unwrap();
+
+        assert_eq!(game_path, library.join("space-game"));
+        assert!(game_path.join("index.html").is_file());
+        assert!(game_path.join(".cursorrules").is_file());
+        assert!(!game_path.join(".git").join("HEAD").exists());
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_game() {
+        let temp = TempDir::new();
+        let library = temp.0.join("library");
+        fs::create_dir(&library).unwrap();
+        let zip_path = temp.0.join("slip.zip");
+        write_zip(&zip_path, |zip| {
+            add_file(zip, "game/index.html", b"<html></html>");
+            add_file(zip, "game/../../escaped.txt", b"gotcha");
+        });
+
+        let result = import_game_zip(&zip_path, &library);
+
+        assert!(matches!(result, Err(GroveError::InvalidArchive { .. })));
+        assert!(!temp.0.join("escaped.txt").exists());
+        assert_library_empty(&library);
+    }
+
+    #[test]
+    fn rejects_symbolic_links() {
+        let temp = TempDir::new();
+        let library = temp.0.join("library");
+        fs::create_dir(&library).unwrap();
+        let zip_path = temp.0.join("link.zip");
+        write_zip(&zip_path, |zip| {
+            add_file(zip, "game/index.html", b"<html></html>");
+            zip.add_symlink("game/secrets", "/etc/passwd", SimpleFileOptions::default()).unwrap();
+        });
+
+        let result = import_game_zip(&zip_path, &library);
+
+        assert!(matches!(result, Err(GroveError::InvalidArchive { .. })));
+        assert_library_empty(&library);
+    }
+
+    #[test]
+    fn rejects_archives_over_the_size_cap() {
+        let temp = TempDir::new();
+        let library = temp.0.join("library");
+        fs::create_dir(&library).unwrap();
+        let zip_path = temp.0.join("bomb.zip");
+        write_zip(&zip_path, |zip| {
+            let options = SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .large_file(true);
+            zip.start_file("game/index.html", options).unwrap();
+            let chunk = vec![0u8; 1024 * 1024];
+            for _ in 0..=MAX_IMPORT_SIZE / chunk.len() as u64 {
+                zip.write_all(&chunk).unwrap();
+            }
+        });
+
+        let result = import_game_zip(&zip_path, &library);
+
+        assert!(matches!(result, Err(GroveError::ArchiveTooLarge { .. })));
+        assert_library_empty(&library);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exports_without_following_symlinks() {
+        let temp = TempDir::new();
+        let game = temp.0.join("game");
+        fs::create_dir(&game).unwrap();
+        fs::write(game.join("index.html"), "<html></html>").unwrap();
+        fs::write(temp.0.join("private.txt"), "not part of the game").unwrap();
+        std::os::unix::fs::symlink(&game, game.join("loop")).unwrap();
+        std::os::unix::fs::symlink(temp.0.join("private.txt"), game.join("private.txt")).unwrap();
+        let zip_path = temp.0.join("game.zip");
+
+        export_game_zip(&game, &zip_path).unwrap();
+
+        let archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert_eq!(names, ["game/index.html"]);
+    }
+
+    #[test]
+    fn finds_the_single_top_folder() {
+        let wrapped = [entry("game", true), entry("game/index.html", false), entry("game/js/main.js", false)];
+        assert_eq!(single_top_folder(&wrapped), Some("game".to_string()));
+
+        // Zips made by hand often have no entry for the folder itself
+        let implicit = [entry("game/index.html", false)];
+        assert_eq!(single_top_folder(&implicit), Some("game".to_string()));
+
+        let loose = [entry("index.html", false), entry("js/main.js", false)];
+        assert_eq!(single_top_folder(&loose), None);
+
+        let two_folders = [entry("game/index.html", false), entry("other/index.html", false)];
+        assert_eq!(single_top_folder(&two_folders), None);
+    }
+}
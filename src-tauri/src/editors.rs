@@ -0,0 +1,254 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use serde::Serialize;
+
+use crate::error::GroveError;
+
+// Always available, opens files with whatever the system uses for text
+pub const TEXT_EDITOR_ID: &str = "text-editor";
+
+// How an editor is told to jump to a line
+#[derive(Clone, Copy)]
+enum LineArgument {
+    Goto,  // --goto file:line, the VS Code family
+    Colon, // file:line
+}
+
+struct EditorSpec {
+    id: &'static str,
+    name: &'static str,
+    commands: &'static [&'static str], // Looked up on PATH
+    // Where installers put the editor when its command isn't on PATH.
+    // "~" is the home folder, %VAR% an environment variable.
+    locations: &'static [&'static str],
+    line_argument: LineArgument,
+}
+
+// In the order they're preferred when a profile hasn't picked one
+const EDITORS: &[EditorSpec] = &[
+    EditorSpec {
+        id: "cursor",
+        name: "Cursor",
+        commands: &["cursor"],
+        locations: &[
+            "/Applications/Cursor.app/Contents/Resources/app/bin/cursor",
+            "~/Applications/Cursor.app/Contents/Resources/app/bin/cursor",
+            "%LOCALAPPDATA%/Programs/cursor/resources/app/bin/cursor.cmd",
+            "/opt/Cursor/resources/app/bin/cursor",
+        ],
+        line_argument: LineArgument::Goto,
+    },
+    EditorSpec {
+        id: "vscode",
+        name: "VS Code",
+        commands: &["code"],
+        locations: &[
+            "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            "~/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            "%LOCALAPPDATA%/Programs/Microsoft VS Code/bin/code.cmd",
+            "%PROGRAMFILES%/Microsoft VS Code/bin/code.cmd",
+            "/snap/bin/code",
+        ],
+        line_argument: LineArgument::Goto,
+    },
+    EditorSpec {
+        id: "vscodium",
+        name: "VSCodium",
+        commands: &["codium"],
+        locations: &[
+            "/Applications/VSCodium.app/Contents/Resources/app/bin/codium",
+            "~/Applications/VSCodium.app/Contents/Resources/app/bin/codium",
+            "%LOCALAPPDATA%/Programs/VSCodium/bin/codium.cmd",
+            "%PROGRAMFILES%/VSCodium/bin/codium.cmd",
+            "/snap/bin/codium",
+        ],
+        line_argument: LineArgument::Goto,
+    },
+    EditorSpec {
+        id: "zed",
+        name: "Zed",
+        commands: &["zed", "zeditor"],
+        locations: &[
+            "/Applications/Zed.app/Contents/MacOS/cli",
+            "~/Applications/Zed.app/Contents/MacOS/cli",
+            "%LOCALAPPDATA%/Programs/Zed/bin/zed.exe",
+            "~/.local/bin/zed",
+        ],
+        line_argument: LineArgument::Colon,
+    },
+    EditorSpec {
+        id: "sublime",
+        name: "Sublime Text",
+        commands: &["subl"],
+        locations: &[
+            "/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl",
+            "~/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl",
+            "%PROGRAMFILES%/Sublime Text/subl.exe",
+            "/opt/sublime_text/sublime_text",
+        ],
+        line_argument: LineArgument::Colon,
+    },
+];
+
+#[derive(Serialize, Clone)]
+pub struct Editor {
+    pub id: String,
+    pub name: String,
+    pub installed: bool,
+}
+
+// Every editor we know how to launch, installed or not, with the text
+// editor fallback last
+pub fn list_editors() -> Vec<Editor> {
+    let mut editors: Vec<Editor> = EDITORS
+        .iter()
+        .map(|spec| Editor {
+            id: spec.id.to_string(),
+            name: spec.name.to_string(),
+            installed: find_executable(spec).is_some(),
+        })
+        .collect();
+
+    editors.push(Editor {
+        id: TEXT_EDITOR_ID.to_string(),
+        name: "Text Editor".to_string(),
+        installed: true,
+    });
+
+    editors
+}
+
+// The first installed editor, falling back to the plain text editor
+pub fn default_editor_id() -> &'static str {
+    EDITORS
+        .iter()
+        .find(|spec| find_executable(spec).is_some())
+        .map(|spec| spec.id)
+        .unwrap_or(TEXT_EDITOR_ID)
+}
+
+pub fn is_known_editor(editor_id: &str) -> bool {
+    editor_id == TEXT_EDITOR_ID || EDITORS.iter().any(|spec| spec.id == editor_id)
+}
+
+// Opens a game folder, and optionally one of its files at a line
+pub fn open_in_editor(editor_id: &str, folder: &Path, file: Option<&Path>, line: Option<u32>) -> Result<(), GroveError> {
+    if editor_id == TEXT_EDITOR_ID {
+        return open_in_text_editor(folder, file);
+    }
+
+    let spec = EDITORS
+        .iter()
+        .find(|spec| spec.id == editor_id)
+        .ok_or_else(|| GroveError::EditorNotInstalled { editor: editor_id.to_string() })?;
+    let executable = find_executable(spec)
+        .ok_or_else(|| GroveError::EditorNotInstalled { editor: spec.name.to_string() })?;
+
+    let mut command = Command::new(executable);
+    command.arg(folder);
+
+    if let Some(file) = file {
+        let target = match line {
+            Some(line) => format!("{}:{}", file.display(), line),
+            None => file.display().to_string(),
+        };
+        if let LineArgument::Goto = spec.line_argument {
+            command.arg("--goto");
+        }
+        command.arg(target);
+    }
+
+    command
+        .spawn()
+        .map_err(|_| GroveError::EditorNotInstalled { editor: spec.name.to_string() })?;
+
+    Ok(())
+}
+
+fn open_in_text_editor(folder: &Path, file: Option<&Path>) -> Result<(), GroveError> {
+    let target = file.unwrap_or(folder);
+
+    // On macOS, -t opens with the default text editor
+    #[cfg(target_os = "macos")]
+    {
+        let mut command = Command::new("open");
+        if file.is_some() {
+            command.arg("-t");
+        }
+        command
+            .arg(target)
+            .spawn()
+            .map_err(|e| GroveError::io("open_text_editor", e))?;
+    }
+
+    // On Windows, Notepad for files and Explorer for the folder
+    #[cfg(target_os = "windows")]
+    {
+        let program = if file.is_some() { "notepad" } else { "explorer" };
+        Command::new(program)
+            .arg(target)
+            .spawn()
+            .map_err(|e| GroveError::io("open_text_editor", e))?;
+    }
+
+    // On Linux, whatever xdg-open picks
+    #[cfg(target_os = "linux")]
+    {
+        Command::new("xdg-open")
+            .arg(target)
+            .spawn()
+            .map_err(|e| GroveError::io("open_text_editor", e))?;
+    }
+
+    Ok(())
+}
+
+fn find_executable(spec: &EditorSpec) -> Option<PathBuf> {
+    spec.commands
+        .iter()
+        .find_map(|command| find_on_path(command))
+        .or_else(|| {
+            spec.locations
+                .iter()
+                .filter_map(|location| expand_location(location))
+                .find(|path| path.is_file())
+        })
+}
+
+fn find_on_path(command: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+
+    // Windows installs editor commands as .cmd or .exe next to each other
+    let file_names: Vec<String> = if cfg!(target_os = "windows") {
+        vec![format!("{}.cmd", command), format!("{}.exe", command)]
+    } else {
+        vec![command.to_string()]
+    };
+
+    env::split_paths(&path)
+        .flat_map(|dir| file_names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+// Turns "~/x" and "%VAR%/x" into real paths. Locations for other systems
+// expand to paths that don't exist, or not at all when the variable is unset.
+fn expand_location(location: &str) -> Option<PathBuf> {
+    if let Some(rest) = location.strip_prefix("~/") {
+        return home_dir().map(|home| home.join(rest));
+    }
+
+    if let Some(rest) = location.strip_prefix('%') {
+        let (variable, rest) = rest.split_once('%')?;
+        let base = env::var_os(variable)?;
+        return Some(PathBuf::from(base).join(rest.trim_start_matches('/')));
+    }
+
+    Some(PathBuf::from(location))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
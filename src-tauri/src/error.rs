@@ -0,0 +1,168 @@
+use std::fmt;
+use std::path::Path;
+use serde::{Serialize, Serializer};
+
+// Every command fails with a GroveError. The frontend gets the variant as
+// `kind` together with its fields, a `message_key` to look up localized,
+// kid-friendly help, and an English `message` to fall back on.
+#[derive(Debug, Serialize)]
+#[serde(remote = "Self", tag = "kind", rename_all = "snake_case")]
+pub enum GroveError {
+    NotFound { path: String },
+    NotADirectory { path: String },
+    AlreadyExists { name: String },
+    InvalidName { name: String, reason: NameProblem, max_length: usize },
+    NoGamesRoot,
+    OutsideGamesRoot { path: String },
+    MissingIndexHtml { path: String },
+    TemplateMissing { template_id: String },
+    SnapshotNotFound { snapshot_id: String },
+    NotInTrash { id: String },
+    EditorNotInstalled { editor: String },
+    ProfileNotFound { id: String },
+    GamesRootTaken { path: String, profile: String },
+    ParentPinRequired,
+    WrongPin { attempts_left: u32 },
+    PinLocked { seconds: u64 },
+    InvalidPin { min_digits: usize, max_digits: usize },
+    InvalidArchive { details: String },
+    ArchiveTooLarge { max_bytes: u64 },
+    NoNetwork,
+    PortInUse { port: u16 },
+    Io { operation: String, details: String },
+    History { details: String },
+    Updater { details: String },
+    Unsupported { feature: String },
+    Internal { details: String },
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NameProblem {
+    Empty,
+    TooLong,
+    InvisibleCharacters,
+    Reserved,
+}
+
+impl GroveError {
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            GroveError::NotFound { .. } => "errors.not_found",
+            GroveError::NotADirectory { .. } => "errors.not_a_directory",
+            GroveError::AlreadyExists { .. } => "errors.already_exists",
+            GroveError::InvalidName { reason, .. } => match reason {
+                NameProblem::Empty => "errors.invalid_name.empty",
+                NameProblem::TooLong => "errors.invalid_name.too_long",
+                NameProblem::InvisibleCharacters => "errors.invalid_name.invisible_characters",
+                NameProblem::Reserved => "errors.invalid_name.reserved",
+            },
+            GroveError::NoGamesRoot => "errors.no_games_root",
+            GroveError::OutsideGamesRoot { .. } => "errors.outside_games_root",
+            GroveError::MissingIndexHtml { .. } => "errors.missing_index_html",
+            GroveError::TemplateMissing { .. } => "errors.template_missing",
+            GroveError::SnapshotNotFound { .. } => "errors.snapshot_not_found",
+            GroveError::NotInTrash { .. } => "errors.not_in_trash",
+            GroveError::EditorNotInstalled { .. } => "errors.editor_not_installed",
+            GroveError::ProfileNotFound { .. } => "errors.profile_not_found",
+            GroveError::GamesRootTaken { .. } => "errors.games_root_taken",
+            GroveError::ParentPinRequired => "errors.parent_pin_required",
+            GroveError::WrongPin { .. } => "errors.wrong_pin",
+            GroveError::PinLocked { .. } => "errors.pin_locked",
+            GroveError::InvalidPin { .. } => "errors.invalid_pin",
+            GroveError::InvalidArchive { .. } => "errors.invalid_archive",
+            GroveError::ArchiveTooLarge { .. } => "errors.archive_too_large",
+            GroveError::NoNetwork => "errors.no_network",
+            GroveError::PortInUse { .. } => "errors.port_in_use",
+            GroveError::Io { .. } => "errors.io",
+            GroveError::History { .. } => "errors.history",
+            GroveError::Updater { .. } => "errors.updater",
+            GroveError::Unsupported { .. } => "errors.unsupported",
+            GroveError::Internal { .. } => "errors.internal",
+        }
+    }
+
+    pub fn not_found(path: &Path) -> GroveError {
+        GroveError::NotFound { path: path.to_string_lossy().to_string() }
+    }
+
+    pub fn not_a_directory(path: &Path) -> GroveError {
+        GroveError::NotADirectory { path: path.to_string_lossy().to_string() }
+    }
+
+    pub fn invalid_name(name: &str, reason: NameProblem, max_length: usize) -> GroveError {
+        GroveError::InvalidName { name: name.to_string(), reason, max_length }
+    }
+
+    // `operation` says what we were doing, like "copy_game_files"
+    pub fn io(operation: &str, error: impl fmt::Display) -> GroveError {
+        GroveError::Io { operation: operation.to_string(), details: error.to_string() }
+    }
+
+    pub fn history(error: impl fmt::Display) -> GroveError {
+        GroveError::History { details: error.to_string() }
+    }
+
+    pub fn internal(error: impl fmt::Display) -> GroveError {
+        GroveError::Internal { details: error.to_string() }
+    }
+}
+
+impl fmt::Display for GroveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GroveError::NotFound { path } => write!(f, "Could not find: {}", path),
+            GroveError::NotADirectory { path } => write!(f, "This is not a folder: {}", path),
+            GroveError::AlreadyExists { name } => write!(f, "There is already something called \"{}\"", name),
+            GroveError::InvalidName { name, reason, max_length } => match reason {
+                NameProblem::Empty => write!(f, "Please enter a name"),
+                NameProblem::TooLong => write!(f, "Names can be at most {} characters long", max_length),
+                NameProblem::InvisibleCharacters => write!(f, "Names can't contain invisible characters"),
+                NameProblem::Reserved => write!(f, "\"{}\" can't be used as a name, please pick another one", name),
+            },
+            GroveError::NoGamesRoot => write!(f, "Please choose a games folder first"),
+            GroveError::OutsideGamesRoot { path } => write!(f, "This is not inside your games folder: {}", path),
+            GroveError::MissingIndexHtml { path } => write!(f, "This game has no index.html to play: {}", path),
+            GroveError::TemplateMissing { template_id } => write!(f, "Could not find the \"{}\" game starter", template_id),
+            GroveError::SnapshotNotFound { snapshot_id } => write!(f, "Could not find that saved version: {}", snapshot_id),
+            GroveError::NotInTrash { id } => write!(f, "That game is not in the trash anymore: {}", id),
+            GroveError::EditorNotInstalled { editor } => write!(f, "{} is not installed on this computer", editor),
+            GroveError::ProfileNotFound { id } => write!(f, "Could not find that player: {}", id),
+            GroveError::GamesRootTaken { path, profile } => write!(f, "{} already keeps their games here: {}", profile, path),
+            GroveError::ParentPinRequired => write!(f, "Ask a grown-up to type their PIN"),
+            GroveError::WrongPin { attempts_left } => write!(f, "That PIN isn't right, {} tries left", attempts_left),
+            GroveError::PinLocked { seconds } => write!(f, "Too many wrong PINs, try again in {} seconds", seconds),
+            GroveError::InvalidPin { min_digits, max_digits } => write!(f, "The PIN needs {} to {} digits", min_digits, max_digits),
+            GroveError::InvalidArchive { details } => write!(f, "This zip file doesn't hold a game we can open: {}", details),
+            GroveError::ArchiveTooLarge { max_bytes } => write!(f, "This zip file is too big, games can be at most {} MB", max_bytes / (1024 * 1024)),
+            GroveError::NoNetwork => write!(f, "Connect to Wi-Fi first, then other devices can play your game"),
+            GroveError::PortInUse { port } => write!(f, "Port {} is already used by another app, try a different number", port),
+            GroveError::Io { operation, details } => write!(f, "Something went wrong ({}): {}", operation, details),
+            GroveError::History { details } => write!(f, "Something went wrong with the game's history: {}", details),
+            GroveError::Updater { details } => write!(f, "Could not check for updates: {}", details),
+            GroveError::Unsupported { feature } => write!(f, "{} is not supported on this computer", feature),
+            GroveError::Internal { details } => write!(f, "Something went wrong: {}", details),
+        }
+    }
+}
+
+impl std::error::Error for GroveError {}
+
+impl Serialize for GroveError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Payload<'a> {
+            #[serde(flatten, with = "GroveError")]
+            error: &'a GroveError,
+            message_key: &'static str,
+            message: String,
+        }
+
+        Payload {
+            error: self,
+            message_key: self.message_key(),
+            message: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
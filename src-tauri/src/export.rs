@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use regex::{Captures, Regex};
+
+use crate::error::GroveError;
+use crate::server;
+
+// Packs a game into one HTML file that runs anywhere, so it can be emailed
+// or AirDropped without the folder it came from. Local scripts and styles
+// are inlined, everything else the page points at becomes a data URI.
+// Remote URLs are left alone.
+
+// Files a quoted path in a script can point at, like new Audio("jump.mp3")
+const SCRIPT_ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "mp3", "wav", "ogg", "m4a",
+    "mp4", "webm", "woff", "woff2", "ttf", "otf", "json", "gltf", "glb",
+];
+
+pub fn export_single_html(game_path: &Path, out_path: &Path) -> Result<(), GroveError> {
+    let game_path = game_path.canonicalize()
+        .map_err(|_| GroveError::not_found(game_path))?;
+    let index_path = game_path.join("index.html");
+
+    let html = fs::read_to_string(&index_path)
+        .map_err(|_| GroveError::MissingIndexHtml { path: game_path.to_string_lossy().to_string() })?;
+
+    let html = inline_scripts(&html, &game_path);
+    let html = inline_stylesheets(&html, &game_path);
+    let html = inline_attributes(&html, &game_path);
+    let html = inline_css_urls(&html, &game_path, &game_path);
+
+    fs::write(out_path, html)
+        .map_err(|e| GroveError::io("write_export", e))
+}
+
+// <script src="game.js"></script> becomes <script>...</script>
+fn inline_scripts(html: &str, game_path: &Path) -> String {
+    let script = Regex::new(r#"(?is)<script\b([^>]*?)\s+src\s*=\s*(?:"([^"]*)"|'([^']*)')([^>]*)>\s*</script>"#).unwrap();
+
+    let html = script
+        .replace_all(html, |captures: &Captures| {
+            let src = quoted(captures, 2, 3);
+            let contents = match local_file(game_path, game_path, src).and_then(|path| fs::read_to_string(path).ok()) {
+                Some(contents) => contents,
+                None => return captures[0].to_string(),
+            };
+
+            format!(
+                "<script{}{}>{}</script>",
+                &captures[1],
+                &captures[4],
+                contents.replace("</script", "<\\/script"),
+            )
+        })
+        .to_string();
+
+    // Assets named in scripts, inlined or written straight into the page.
+    // Paths in scripts are relative to the page, not the script file.
+    let inline_script = Regex::new(r"(?is)(<script\b[^>]*>)(.*?)(</script>)").unwrap();
+    inline_script
+        .replace_all(&html, |captures: &Captures| {
+            format!("{}{}{}", &captures[1], inline_script_strings(&captures[2], game_path), &captures[3])
+        })
+        .to_string()
+}
+
+// <link rel="stylesheet" href="style.css"> becomes <style>...</style>
+fn inline_stylesheets(html: &str, game_path: &Path) -> String {
+    let link = Regex::new(r"(?is)<link\b[^>]*>").unwrap();
+    let rel = Regex::new(r#"(?i)\brel\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    let href = Regex::new(r#"(?i)\bhref\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    link.replace_all(html, |captures: &Captures| {
+        let tag = &captures[0];
+        let is_stylesheet = rel
+            .captures(tag)
+            .is_some_and(|rel| quoted(&rel, 1, 2).to_ascii_lowercase().contains("stylesheet"));
+        let path = href
+            .captures(tag)
+            .and_then(|href| local_file(game_path, game_path, quoted(&href, 1, 2)));
+
+        match (is_stylesheet, path) {
+            (true, Some(path)) => match fs::read_to_string(&path) {
+                Ok(css) => {
+                    // url() in a stylesheet is relative to the stylesheet
+                    let css_dir = path.parent().unwrap_or(game_path);
+                    format!("<style>{}</style>", inline_css_urls(&css, css_dir, game_path))
+                }
+                Err(_) => tag.to_string(),
+            },
+            // Icons and other links are handled with the other attributes
+            _ => tag.to_string(),
+        }
+    })
+    .to_string()
+}
+
+// src, href and poster pointing at local files, in tags and in inline scripts
+fn inline_attributes(html: &str, game_path: &Path) -> String {
+    let attribute = Regex::new(r#"(?i)\b(src|href|poster)(\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap();
+
+    attribute
+        .replace_all(html, |captures: &Captures| {
+            let value = quoted(captures, 3, 4);
+            // Links to other pages stay links
+            if captures[1].eq_ignore_ascii_case("href") && is_page(value) {
+                return captures[0].to_string();
+            }
+
+            match local_file(game_path, game_path, value).and_then(|path| data_uri(&path)) {
+                Some(uri) => format!("{}{}\"{}\"", &captures[1], &captures[2], uri),
+                None => captures[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+// url(...) in CSS, which is where fonts and background images live
+fn inline_css_urls(css: &str, base_dir: &Path, game_path: &Path) -> String {
+    let url = Regex::new(r#"(?i)url\(\s*(?:"([^"]*)"|'([^']*)'|([^)\s"']*))\s*\)"#).unwrap();
+
+    url.replace_all(css, |captures: &Captures| {
+        let value = captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .or_else(|| captures.get(3))
+            .map_or("", |value| value.as_str());
+
+        // Data URIs have no quotes or spaces, so they're safe inside style="..." too
+        match local_file(base_dir, game_path, value).and_then(|path| data_uri(&path)) {
+            Some(uri) => format!("url({})", uri),
+            None => captures[0].to_string(),
+        }
+    })
+    .to_string()
+}
+
+// Quoted strings in a script that name an asset in the game
+fn inline_script_strings(script: &str, game_path: &Path) -> String {
+    let string = Regex::new(r#""([^"\\\n]*)"|'([^'\\\n]*)'"#).unwrap();
+
+    string
+        .replace_all(script, |captures: &Captures| {
+            let value = quoted(captures, 1, 2);
+            let is_asset = Path::new(value)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SCRIPT_ASSET_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+            if !is_asset {
+                return captures[0].to_string();
+            }
+
+            match local_file(game_path, game_path, value).and_then(|path| data_uri(&path)) {
+                Some(uri) => format!("\"{}\"", uri),
+                None => captures[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+fn quoted<'a>(captures: &'a Captures, double: usize, single: usize) -> &'a str {
+    captures
+        .get(double)
+        .or_else(|| captures.get(single))
+        .map_or("", |value| value.as_str())
+}
+
+fn is_page(value: &str) -> bool {
+    let path = value.split(['?', '#']).next().unwrap_or(value).to_ascii_lowercase();
+    path.ends_with(".html") || path.ends_with(".htm") || path.ends_with('/')
+}
+
+// Resolves a reference to a file inside the game. Remote URLs, data URIs,
+// anchors and anything that leaves the game folder resolve to nothing.
+fn local_file(base_dir: &Path, game_path: &Path, reference: &str) -> Option<PathBuf> {
+    let reference = reference.trim();
+    if reference.is_empty()
+        || reference.starts_with('#')
+        || reference.starts_with("//")
+        || reference.contains(':')
+    {
+        return None;
+    }
+
+    // Query strings and fragments aren't part of the file name
+    let path = reference.split(['?', '#']).next()?;
+    let path = percent_encoding::percent_decode_str(path).decode_utf8().ok()?;
+
+    let resolved = match path.strip_prefix('/') {
+        Some(from_root) => game_path.join(from_root),
+        None => base_dir.join(path.as_ref()),
+    };
+    let resolved = resolved.canonicalize().ok()?;
+
+    if resolved.starts_with(game_path) && resolved.is_file() {
+        Some(resolved)
+    } else {
+        None
+    }
+}
+
+fn data_uri(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    let mime = server::content_type(path).replace(' ', "");
+
+    Some(format!("data:{};base64,{}", mime, BASE64.encode(bytes)))
+}
@@ -0,0 +1,181 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::path::BaseDirectory;
+use tauri::Manager;
+
+// Names that are never part of a game itself: version control, installed
+// packages, Finder's clutter and the app's own .grove folder. Other dotfiles,
+// like the .cursorrules the starters ship, belong to the game.
+const IGNORED_NAMES: &[&str] = &[".git", ".grove", "node_modules", ".DS_Store"];
+
+pub fn is_ignored_name(name: &OsStr) -> bool {
+    IGNORED_NAMES.iter().any(|ignored| name == *ignored)
+}
+
+// Where a folder shipped from src/, like the templates or libs, can be found:
+// bundled with the app first, then in the source tree while developing
+pub fn bundled_dirs(app_handle: &tauri::AppHandle, name: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    // Bundled resources first
+    if let Ok(resource_dir) = app_handle.path().resolve(name, BaseDirectory::Resource) {
+        dirs.push(resource_dir);
+    }
+
+    // Fallback to development mode - look in the source tree
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let project_root = find_project_root();
+    dirs.push(current_dir.join("src").join(name));
+    dirs.push(current_dir.join("..").join("src").join(name));
+    dirs.push(project_root.join("..").join("src").join(name));
+
+    dirs
+}
+
+fn find_project_root() -> PathBuf {
+    let mut current = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+
+    loop {
+        if current.join("Cargo.toml").exists() {
+            return current;
+        }
+
+        if let Some(parent) = current.parent() {
+            current = parent.to_path_buf();
+        } else {
+            break;
+        }
+    }
+
+    // Fallback to current directory if we can't find Cargo.toml
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+pub fn copy_dir_contents(source: &Path, target: &Path) -> Result<(), std::io::Error> {
+    copy_dir_filtered(source, target, &|_| false)
+}
+
+// Copies only what belongs to the game, leaving out ignored files and folders
+pub fn copy_game_contents(source: &Path, target: &Path) -> Result<(), std::io::Error> {
+    copy_dir_filtered(source, target, &is_ignored_name)
+}
+
+fn copy_dir_filtered(source: &Path, target: &Path, skip: &dyn Fn(&OsStr) -> bool) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let source_path = entry.path();
+        let file_name = entry.file_name();
+        if skip(&file_name) {
+            continue;
+        }
+        let target_path = target.join(file_name);
+        
+        if file_type.is_file() {
+            fs::copy(&source_path, &target_path)?;
+        } else if file_type.is_dir() {
+            fs::create_dir(&target_path)?;
+            copy_dir_filtered(&source_path, &target_path, skip)?;
+        } else if file_type.is_symlink() {
+            // Copied as a link, pointing wherever the original pointed
+            copy_symlink(&source_path, &target_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), std::io::Error> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, target: &Path) -> Result<(), std::io::Error> {
+    let link = fs::read_link(source)?;
+    if source.is_dir() {
+        std::os::windows::fs::symlink_dir(link, target)
+    } else {
+        std::os::windows::fs::symlink_file(link, target)
+    }
+}
+
+// Why a move failed. Once the copy is complete the target is the one whole
+// copy of the folder, so it must be kept even when the source can't be
+// removed, or is only partly removed.
+#[derive(Debug)]
+pub enum MoveError {
+    Copy(std::io::Error),
+    RemoveSource(std::io::Error),
+}
+
+// Moves a folder, falling back to copy and delete when the target is on
+// another disk and a plain rename isn't possible.
+pub fn move_dir(source: &Path, target: &Path) -> Result<(), MoveError> {
+    if fs::rename(source, target).is_ok() {
+        return Ok(());
+    }
+
+    fs::create_dir(target).map_err(MoveError::Copy)?;
+    if let Err(e) = copy_dir_contents(source, target) {
+        let _ = fs::remove_dir_all(target);
+        return Err(MoveError::Copy(e));
+    }
+    fs::remove_dir_all(source).map_err(MoveError::RemoveSource)
+}
+
+// Picks "Space Cat", then "Space Cat (2)", "Space Cat (3)" and so on until
+// nothing in the parent folder has that name yet.
+pub fn unique_folder_name(parent: &Path, base_name: &str) -> String {
+    if !parent.join(base_name).exists() {
+        return base_name.to_string();
+    }
+
+    let mut counter = 2;
+    loop {
+        let candidate = format!("{} ({})", base_name, counter);
+        if !parent.join(&candidate).exists() {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+// Dotfiles and installed packages change without anyone working on the game,
+// like editor settings or the app's own thumbnail and history
+fn is_ignored_for_last_modified(name: &OsStr) -> bool {
+    name.to_string_lossy().starts_with('.') || name == "node_modules"
+}
+
+// The newest modification time of anything inside a folder. A folder's own
+// mtime only changes when entries are added or removed directly inside it,
+// so editing a file never bumps it.
+pub fn newest_modified(path: &Path) -> u64 {
+    let mut newest = modified_time(path);
+
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            if is_ignored_for_last_modified(&entry.file_name()) {
+                continue;
+            }
+
+            let entry_path = entry.path();
+            let modified = match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => newest_modified(&entry_path),
+                Ok(_) => modified_time(&entry_path),
+                Err(_) => 0,
+            };
+            newest = newest.max(modified);
+        }
+    }
+
+    newest
+}
+
+fn modified_time(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|time| time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+        .unwrap_or(0)
+}
@@ -1,13 +1,35 @@
+use std::collections::hash_map::Entry;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri_plugin_store::StoreExt;
 use serde_json::json;
-use tauri::{AppHandle, Manager};
-use tauri::path::BaseDirectory;
+use tauri::AppHandle;
 
+mod archive;
+mod editors;
+mod error;
+mod export;
+mod files;
+mod libraries;
+mod library;
+mod manifest;
 mod menu;
+mod parental;
+mod profiles;
+mod search;
+mod server;
+mod settings;
+mod share;
+mod snapshots;
+mod templates;
+mod thumbnails;
+mod trash;
+mod validation;
+
+use error::GroveError;
+use manifest::{GameManifest, TemplateInfo};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -15,327 +37,873 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct FolderEntry {
     name: String,
     path: String,
     last_modified: u64, // Unix timestamp
+    manifest: Option<GameManifest>,
+    thumbnail: Option<String>, // Path to a PNG captured while the game was running
+    root: Option<String>, // Library root the game lives in
 }
 
-#[tauri::command]
-fn read_src_folders() -> Result<Vec<FolderEntry>, String> {
-    // Get the home directory
-    let home_dir = dirs::home_dir()
-        .ok_or_else(|| "Could not find home directory".to_string())?;
+fn folder_entry(path: &Path) -> Option<FolderEntry> {
+    if !path.is_dir() {
+        return None;
+    }
     
-    // Build the path to ~/src
-    let src_path = home_dir.join("src");
+    let name_str = path.file_name()?.to_str()?;
     
-    // Check if the directory exists
-    if !src_path.exists() {
-        return Ok(Vec::new()); // Return empty list if ~/src doesn't exist
+    // Hidden folders are never games, like an import that is still unpacking
+    if name_str.starts_with('.') {
+        return None;
     }
     
-    // Read the directory
-    let entries = fs::read_dir(&src_path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-    
-    // Filter for directories only and collect their names
+    Some(FolderEntry {
+        name: name_str.to_string(),
+        path: path.to_string_lossy().to_string(),
+        // Newest file inside the game, the folder's own mtime misses edits
+        last_modified: library::last_modified(path),
+        manifest: manifest::read_manifest(path),
+        thumbnail: thumbnails::existing_thumbnail(path),
+        // Games are direct children of their library root
+        root: path.parent().map(|root| root.to_string_lossy().to_string()),
+    })
+}
+
+#[tauri::command]
+fn read_library_games(app_handle: tauri::AppHandle) -> Result<Vec<FolderEntry>, GroveError> {
     let mut folders = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(name) = path.file_name() {
-                    if let Some(name_str) = name.to_str() {
-                        // Get last modified timestamp
-                        let last_modified = match fs::metadata(&path) {
-                            Ok(metadata) => {
-                                match metadata.modified() {
-                                    Ok(time) => time.duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or_default()
-                                        .as_secs(),
-                                    Err(_) => 0,
-                                }
-                            },
-                            Err(_) => 0,
-                        };
-                        
-                        folders.push(FolderEntry {
-                            name: name_str.to_string(),
-                            path: path.to_string_lossy().to_string(),
-                            last_modified,
-                        });
-                    }
-                }
+    
+    for root in settings::library_roots(&app_handle) {
+        // A root on a drive that isn't plugged in shouldn't hide the other roots
+        let entries = match fs::read_dir(&root.path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("Failed to read library root {}: {}", root.path, e);
+                continue;
+            }
+        };
+        
+        for entry in entries.flatten() {
+            if let Some(folder) = folder_entry(&entry.path()) {
+                folders.push(folder);
             }
         }
     }
     
     // Sort folders by last modified (newest first)
-    folders.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    folders.sort_by_key(|folder| std::cmp::Reverse(folder.last_modified));
     
     Ok(folders)
 }
 
 #[tauri::command]
-fn read_folders_from_path(folder_path: String) -> Result<Vec<FolderEntry>, String> {
+fn list_library_roots(app_handle: tauri::AppHandle) -> Result<Vec<settings::LibraryRoot>, GroveError> {
+    Ok(settings::library_roots(&app_handle))
+}
+
+#[tauri::command]
+fn add_library_root(path: String, label: String, color: Option<String>, app_handle: tauri::AppHandle) -> Result<settings::LibraryRoot, GroveError> {
+    // Where games live is for grown-ups to decide
+    parental::require_parent(&app_handle)?;
+    
+    let root_path = PathBuf::from(&path);
+    
+    // Check if the directory exists
+    if !root_path.exists() {
+        return Err(GroveError::not_found(&root_path));
+    }
+    
+    if !root_path.is_dir() {
+        return Err(GroveError::not_a_directory(&root_path));
+    }
+    
+    // Stored resolved, so the same folder can't be added twice under different spellings
+    let root_path = root_path.canonicalize()
+        .map_err(|_| GroveError::not_found(&root_path))?;
+    let label = validation::validate_display_name(&label)?;
+    
+    let mut roots = settings::stored_library_roots(&app_handle);
+    if roots.iter().any(|root| Path::new(&root.path) == root_path) {
+        return Err(GroveError::AlreadyExists { name: label });
+    }
+    
+    let root = settings::LibraryRoot {
+        path: root_path.to_string_lossy().to_string(),
+        label,
+        color: color.unwrap_or_else(|| settings::DEFAULT_ROOT_COLOR.to_string()),
+    };
+    roots.push(root.clone());
+    settings::save_library_roots(&app_handle, &roots)?;
+    
+    Ok(root)
+}
+
+// Only forgets the root, the games in it stay where they are
+#[tauri::command]
+fn remove_library_root(path: String, app_handle: tauri::AppHandle, watchers: tauri::State<library::LibraryWatchers>) -> Result<(), GroveError> {
+    // Where games live is for grown-ups to decide
+    parental::require_parent(&app_handle)?;
+    
+    let root_path = PathBuf::from(&path);
+    
+    let mut roots = settings::stored_library_roots(&app_handle);
+    let count = roots.len();
+    roots.retain(|root| Path::new(&root.path) != root_path);
+    if roots.len() == count {
+        return Err(GroveError::not_found(&root_path));
+    }
+    
+    settings::save_library_roots(&app_handle, &roots)?;
+    settings::clear_selected_games_path(&app_handle, &root_path)?;
+    
+    if let Ok(mut watchers) = watchers.0.lock() {
+        watchers.remove(&root_path);
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+fn list_profiles(app_handle: tauri::AppHandle) -> Result<Vec<profiles::Profile>, GroveError> {
+    Ok(profiles::list_profiles(&app_handle))
+}
+
+#[tauri::command]
+fn get_active_profile(app_handle: tauri::AppHandle) -> Result<Option<profiles::Profile>, GroveError> {
+    Ok(profiles::active_profile(&app_handle))
+}
+
+#[tauri::command]
+fn create_profile(
+    name: String,
+    avatar: Option<String>,
+    games_root: String,
+    editor: Option<String>,
+    theme: Option<profiles::Theme>,
+    age_band: profiles::AgeBand,
+    app_handle: tauri::AppHandle,
+) -> Result<profiles::Profile, GroveError> {
+    parental::require_parent(&app_handle)?;
+    profiles::create_profile(&app_handle, &name, avatar, &games_root, editor, theme, age_band)
+}
+
+#[tauri::command]
+fn update_profile(id: String, changes: profiles::ProfileChanges, app_handle: tauri::AppHandle) -> Result<profiles::Profile, GroveError> {
+    parental::require_parent(&app_handle)?;
+    profiles::update_profile(&app_handle, &id, changes)
+}
+
+#[tauri::command]
+fn delete_profile(id: String, app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::require_parent(&app_handle)?;
+    profiles::delete_profile(&app_handle, &id)
+}
+
+// Pass no id to leave the child's profile
+#[tauri::command]
+fn switch_profile(
+    id: Option<String>,
+    app_handle: tauri::AppHandle,
+    watchers: tauri::State<library::LibraryWatchers>,
+    servers: tauri::State<server::GameServers>,
+    lan_share: tauri::State<server::LanShare>,
+) -> Result<Option<profiles::Profile>, GroveError> {
+    // Otherwise siblings could hop into each other's games
+    parental::require_parent(&app_handle)?;
+    
+    let profile = profiles::set_active_profile(&app_handle, id.as_deref())?;
+    
+    // Watchers and servers started for the previous player would keep
+    // reporting on and serving games the new one can't see
+    if let Ok(mut watchers) = watchers.0.lock() {
+        watchers.clear();
+    }
+    if let Ok(mut servers) = servers.0.lock() {
+        for (_, server) in servers.drain() {
+            server.stop();
+        }
+    }
+    if let Ok(mut lan_share) = lan_share.0.lock() {
+        if let Some(mut server) = lan_share.take() {
+            server.stop();
+        }
+    }
+    
+    Ok(profile)
+}
+
+#[tauri::command]
+fn read_folders_from_path(folder_path: String, app_handle: tauri::AppHandle) -> Result<Vec<FolderEntry>, GroveError> {
     let path = PathBuf::from(&folder_path);
     
+    // Only a games root can be listed
+    validation::ensure_games_root(&app_handle, &path)?;
+    
     // Check if the directory exists
     if !path.exists() {
-        return Err(format!("Directory does not exist: {}", folder_path));
+        return Err(GroveError::not_found(&path));
     }
     
     if !path.is_dir() {
-        return Err(format!("Path is not a directory: {}", folder_path));
+        return Err(GroveError::not_a_directory(&path));
     }
     
     // Read the directory
     let entries = fs::read_dir(&path)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+        .map_err(|e| GroveError::io("read_games_folder", e))?;
     
     // Filter for directories only and collect their names
     let mut folders = Vec::new();
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(name) = path.file_name() {
-                    if let Some(name_str) = name.to_str() {
-                        // Get last modified timestamp
-                        let last_modified = match fs::metadata(&path) {
-                            Ok(metadata) => {
-                                match metadata.modified() {
-                                    Ok(time) => time.duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap_or_default()
-                                        .as_secs(),
-                                    Err(_) => 0,
-                                }
-                            },
-                            Err(_) => 0,
-                        };
-                        
-                        folders.push(FolderEntry {
-                            name: name_str.to_string(),
-                            path: path.to_string_lossy().to_string(),
-                            last_modified,
-                        });
-                    }
-                }
-            }
+    for entry in entries.flatten() {
+        if let Some(folder) = folder_entry(&entry.path()) {
+            folders.push(folder);
         }
     }
     
     // Sort folders by last modified (newest first)
-    folders.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    folders.sort_by_key(|folder| std::cmp::Reverse(folder.last_modified));
     
     Ok(folders)
 }
 
 #[tauri::command]
-fn create_game_folder(parent_path: String, folder_name: String, game_type: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+fn watch_library(root: String, app_handle: tauri::AppHandle, watchers: tauri::State<library::LibraryWatchers>) -> Result<(), GroveError> {
+    let path = PathBuf::from(&root);
+    
+    // Only a games root can be watched
+    validation::ensure_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    let mut watchers = watchers.0.lock().map_err(|_| GroveError::internal("Library watcher state is poisoned"))?;
+    if let Entry::Vacant(entry) = watchers.entry(path) {
+        let watcher = library::watch_library(app_handle, entry.key().clone())?;
+        entry.insert(watcher);
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+fn unwatch_library(root: String, watchers: tauri::State<library::LibraryWatchers>) -> Result<(), GroveError> {
+    let path = PathBuf::from(&root);
+    
+    // Dropping the watcher stops it
+    let mut watchers = watchers.0.lock().map_err(|_| GroveError::internal("Library watcher state is poisoned"))?;
+    watchers.remove(&path);
+    
+    Ok(())
+}
+
+#[tauri::command]
+fn search_games(root: String, query: String, app_handle: tauri::AppHandle) -> Result<Vec<search::SearchResult>, GroveError> {
+    let path = PathBuf::from(&root);
+    
+    // Only a games root can be searched
+    validation::ensure_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    search::search_games(&path, &query)
+}
+
+// What the player chose for a new game, anything left out gets a default
+#[derive(Deserialize, Default)]
+struct NewGameOptions {
+    display_name: Option<String>,
+    icon: Option<String>,
+    author: Option<String>,
+    color: Option<String>,
+}
+
+#[tauri::command]
+fn create_game_folder(
+    parent_path: String,
+    folder_name: String,
+    template_id: String,
+    options: NewGameOptions,
+    app_handle: tauri::AppHandle,
+) -> Result<String, GroveError> {
+    let NewGameOptions { display_name, icon, author, color } = options;
     let parent = PathBuf::from(&parent_path);
     
+    // New games can only be made directly inside a games root
+    validation::ensure_games_root(&app_handle, &parent)?;
+    let folder_name = validation::sanitize_folder_name(&folder_name)?;
+    let display_name = display_name
+        .map(|name| validation::validate_display_name(&name))
+        .transpose()?;
+    
     // Check if parent directory exists
     if !parent.exists() {
-        return Err(format!("Parent directory does not exist: {}", parent_path));
+        return Err(GroveError::not_found(&parent));
     }
     
     if !parent.is_dir() {
-        return Err(format!("Parent path is not a directory: {}", parent_path));
+        return Err(GroveError::not_a_directory(&parent));
     }
     
-    // Validate game type
-    if game_type != "2d" && game_type != "3d" {
-        return Err(format!("Invalid game type: {}. Must be '2d' or '3d'", game_type));
-    }
+    // Look up the template before touching the filesystem
+    let template = templates::find_template(&app_handle, &template_id)?;
     
     // Create the full path for the new folder
     let new_folder_path = parent.join(&folder_name);
     
     // Check if folder already exists
     if new_folder_path.exists() {
-        return Err(format!("Folder already exists: {}", folder_name));
+        return Err(GroveError::AlreadyExists { name: folder_name });
     }
     
     // Create the directory
     fs::create_dir(&new_folder_path)
-        .map_err(|e| format!("Failed to create folder: {}", e))?;
+        .map_err(|e| GroveError::io("create_game_folder", e))?;
     
     // Copy boilerplate files
-    copy_boilerplate_files(&game_type, &new_folder_path, &app_handle)?;
+    copy_boilerplate_files(&template, &new_folder_path)?;
+    
+    let game_name = display_name.unwrap_or_else(|| folder_name.clone());
+    let created_at = manifest::now_unix();
+    
+    // Fill in the game's name, author and colour so the page matches what was typed
+    let variables = templates::TemplateVariables {
+        game_name: game_name.clone(),
+        game_slug: folder_name.clone(),
+        author: author.clone().unwrap_or_default(),
+        date: templates::format_date(created_at),
+        color: color.unwrap_or_else(|| templates::DEFAULT_GAME_COLOR.to_string()),
+    };
+    templates::render_game_files(&new_folder_path, &template, &variables)?;
+    
+    // Libraries the template loads from a CDN get local copies so the game works offline
+    if let Err(e) = libraries::vendor_libraries(&app_handle, &new_folder_path) {
+        println!("Failed to copy game libraries: {}", e);
+    }
+    
+    // Write the manifest last so it can't be overwritten by a boilerplate file
+    let manifest = GameManifest {
+        id: manifest::new_game_id(),
+        name: game_name,
+        icon,
+        game_type: template.game_type.clone(),
+        template: TemplateInfo {
+            name: template.id.clone(),
+            version: template.version.clone()
+                .unwrap_or_else(|| app_handle.package_info().version.to_string()),
+        },
+        created_at,
+        author,
+        remixed_from: None,
+    };
+    manifest::write_manifest(&new_folder_path, &manifest)?;
+    
+    // Start the game's history so there's always a working version to go back to
+    if let Err(e) = snapshots::init_repository(&new_folder_path)
+        .and_then(|_| snapshots::take_snapshot(&new_folder_path, "Created the game"))
+    {
+        println!("Failed to snapshot new game: {}", e);
+    }
     
     Ok(new_folder_path.to_string_lossy().to_string())
 }
 
-fn copy_boilerplate_files(game_type: &str, target_path: &PathBuf, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    // Try to resolve the boilerplate directory from bundled resources first
-    let resource_paths = vec![
-        format!("{}-game-boilerplate", game_type),
-        format!("{}-game-boilerplate/", game_type),
-        format!("../src/{}-game-boilerplate", game_type),
-        format!("../src/{}-game-boilerplate/", game_type),
-    ];
-    
-    for resource_path in &resource_paths {
-        // First try to resolve from bundled resources
-        if let Ok(source_dir) = app_handle.path().resolve(resource_path, BaseDirectory::Resource) {
-            if source_dir.exists() && source_dir.is_dir() {
-                return copy_dir_contents(&source_dir, target_path)
-                    .map_err(|e| format!("Failed to copy boilerplate files from resources: {}", e));
+fn copy_boilerplate_files(template: &templates::TemplateEntry, target_path: &Path) -> Result<(), GroveError> {
+    let source_dir = PathBuf::from(&template.path);
+    
+    let entries = fs::read_dir(&source_dir)
+        .map_err(|e| GroveError::io("read_template", e))?;
+    
+    for entry in entries {
+        let entry = entry.map_err(|e| GroveError::io("read_template", e))?;
+        let file_name = entry.file_name();
+        
+        // The template's own description and preview don't belong in the game
+        if file_name == templates::TEMPLATE_MANIFEST_FILE_NAME {
+            continue;
+        }
+        if let Some(preview) = &template.preview {
+            if entry.path() == Path::new(preview) {
+                continue;
             }
         }
+        
+        let source_path = entry.path();
+        let target = target_path.join(&file_name);
+        let result = if source_path.is_dir() {
+            fs::create_dir(&target).and_then(|_| files::copy_dir_contents(&source_path, &target))
+        } else {
+            fs::copy(&source_path, &target).map(|_| ())
+        };
+        result.map_err(|e| GroveError::io("copy_template_files", e))?;
     }
     
-    // Fallback to development mode - look for boilerplate templates in filesystem
-    let current_dir = std::env::current_dir()
-        .map_err(|e| format!("Failed to get current directory: {}", e))?;
-    
-    // Get the project root by looking for Cargo.toml
-    let project_root = find_project_root();
-    
-    // Look for boilerplate templates in development locations
-    let possible_source_dirs = vec![
-        // For development (from current working directory)
-        current_dir.join("src").join(format!("{}-game-boilerplate", game_type)),
-        // For development (from project root)
-        project_root.join("src").join(format!("{}-game-boilerplate", game_type)),
-        // Try relative path from current directory
-        PathBuf::from("src").join(format!("{}-game-boilerplate", game_type)),
-        // Try relative path from project root
-        project_root.join("src").join(format!("{}-game-boilerplate", game_type)),
-    ];
-    
-    let mut source_dir: Option<PathBuf> = None;
-    let mut checked_paths = Vec::new();
-    
-    for possible_dir in possible_source_dirs {
-        let path_str = possible_dir.to_string_lossy().to_string();
-        checked_paths.push(path_str);
-        if possible_dir.exists() && possible_dir.is_dir() {
-            source_dir = Some(possible_dir);
-            break;
-        }
+    Ok(())
+}
+
+#[tauri::command]
+fn duplicate_game(source_path: String, new_name: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    let source = PathBuf::from(&source_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &source)?;
+    
+    // Check if the directory exists
+    if !source.exists() {
+        return Err(GroveError::not_found(&source));
+    }
+    
+    if !source.is_dir() {
+        return Err(GroveError::not_a_directory(&source));
+    }
+    
+    let new_name = validation::sanitize_folder_name(&new_name)?;
+    let new_name = new_name.as_str();
+    
+    // Remixes live right next to the original
+    let parent = source.parent()
+        .ok_or_else(|| GroveError::not_found(&source))?;
+    let folder_name = files::unique_folder_name(parent, new_name);
+    let new_folder_path = parent.join(&folder_name);
+    
+    fs::create_dir(&new_folder_path)
+        .map_err(|e| GroveError::io("create_game_folder", e))?;
+    
+    // Everything comes along except the snapshot history, the remix starts its own
+    if let Err(e) = files::copy_dir_contents(&source, &new_folder_path) {
+        let _ = fs::remove_dir_all(&new_folder_path);
+        return Err(GroveError::io("copy_game_files", e));
     }
+    snapshots::forget_history(&new_folder_path)?;
+    
+    // The original needs an id for the remix to point at
+    let source_name = source.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut manifest = match manifest::read_manifest(&source) {
+        Some(manifest) => manifest,
+        None => {
+            let manifest = GameManifest::untemplated(&source_name);
+            manifest::write_manifest(&source, &manifest)?;
+            manifest
+        }
+    };
     
-    let source_dir = source_dir.ok_or_else(|| format!(
-        "Could not find {}-game-boilerplate directory. Checked paths: {}", 
-        game_type,
-        checked_paths.join(", ")
-    ))?;
+    manifest.remixed_from = Some(manifest::ParentGame {
+        id: manifest.id.clone(),
+        name: manifest.name.clone(),
+    });
+    manifest.id = manifest::new_game_id();
+    manifest.name = folder_name;
+    manifest.created_at = manifest::now_unix();
+    manifest::write_manifest(&new_folder_path, &manifest)?;
     
-    // Copy all files from the boilerplate directory to the target
-    copy_dir_contents(&source_dir, target_path)
-        .map_err(|e| format!("Failed to copy boilerplate files: {}", e))
+    // The remix gets a history of its own
+    if let Err(e) = snapshots::init_repository(&new_folder_path)
+        .and_then(|_| snapshots::take_snapshot(&new_folder_path, "Remixed the game"))
+    {
+        println!("Failed to snapshot remixed game: {}", e);
+    }
+    
+    Ok(new_folder_path.to_string_lossy().to_string())
 }
 
-fn find_project_root() -> PathBuf {
-    let mut current = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+#[derive(Serialize)]
+struct RenamedGame {
+    path: String,
+    name: String,
+}
+
+#[tauri::command]
+fn rename_game(
+    path: String,
+    new_display_name: String,
+    app_handle: tauri::AppHandle,
+    servers: tauri::State<server::GameServers>,
+    lan_share: tauri::State<server::LanShare>,
+) -> Result<RenamedGame, GroveError> {
+    let game_path = PathBuf::from(&path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &game_path)?;
     
-    loop {
-        if current.join("Cargo.toml").exists() {
-            return current;
+    // Check if the directory exists
+    if !game_path.exists() {
+        return Err(GroveError::not_found(&game_path));
+    }
+    
+    if !game_path.is_dir() {
+        return Err(GroveError::not_a_directory(&game_path));
+    }
+    
+    let new_name = validation::validate_display_name(&new_display_name)?;
+    let parent = game_path.parent()
+        .ok_or_else(|| GroveError::not_found(&game_path))?;
+    let new_path = parent.join(validation::sanitize_folder_name(&new_name)?);
+    
+    // "Space Cat" and "Space Cat?" share a folder name, so only move when it changes
+    if new_path != game_path {
+        // On disks that ignore case, "space cat" is already there when it
+        // becomes "Space Cat", but it's the same folder
+        let is_same_folder = new_path.canonicalize().ok() == game_path.canonicalize().ok();
+        if new_path.exists() && !is_same_folder {
+            return Err(GroveError::AlreadyExists { name: new_name });
         }
         
-        if let Some(parent) = current.parent() {
-            current = parent.to_path_buf();
-        } else {
-            break;
+        // The old server would keep serving a folder that no longer exists
+        if let Ok(mut servers) = servers.0.lock() {
+            if let Some(server) = servers.remove(&game_path) {
+                server.stop();
+            }
         }
+        stop_lan_share_of(&lan_share, &game_path);
+        
+        fs::rename(&game_path, &new_path)
+            .map_err(|e| GroveError::io("rename_game", e))?;
     }
     
-    // Fallback to current directory if we can't find Cargo.toml
-    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+    let old_folder_name = game_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut manifest = manifest::read_manifest(&new_path)
+        .unwrap_or_else(|| GameManifest::untemplated(&old_folder_name));
+    let old_name = std::mem::replace(&mut manifest.name, new_name.clone());
+    manifest::write_manifest(&new_path, &manifest)?;
+    
+    templates::retitle_page(&new_path, &old_name, &new_name)?;
+    
+    Ok(RenamedGame {
+        path: new_path.to_string_lossy().to_string(),
+        name: new_name,
+    })
 }
 
-fn copy_dir_contents(source: &PathBuf, target: &PathBuf) -> Result<(), std::io::Error> {
-    for entry in fs::read_dir(source)? {
-        let entry = entry?;
-        let file_type = entry.file_type()?;
-        let source_path = entry.path();
-        let file_name = entry.file_name();
-        let target_path = target.join(file_name);
-        
-        if file_type.is_file() {
-            fs::copy(&source_path, &target_path)?;
-        } else if file_type.is_dir() {
-            fs::create_dir(&target_path)?;
-            copy_dir_contents(&source_path, &target_path)?;
+#[tauri::command]
+fn trash_game(
+    path: String,
+    app_handle: tauri::AppHandle,
+    servers: tauri::State<server::GameServers>,
+    lan_share: tauri::State<server::LanShare>,
+) -> Result<trash::TrashEntry, GroveError> {
+    parental::require_parent(&app_handle)?;
+    
+    let game_path = PathBuf::from(&path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &game_path)?;
+    
+    // Check if the directory exists
+    if !game_path.exists() {
+        return Err(GroveError::not_found(&game_path));
+    }
+    
+    if !game_path.is_dir() {
+        return Err(GroveError::not_a_directory(&game_path));
+    }
+    
+    // A trashed game can't keep being served
+    if let Ok(mut servers) = servers.0.lock() {
+        if let Some(server) = servers.remove(&game_path) {
+            server.stop();
         }
     }
-    Ok(())
+    stop_lan_share_of(&lan_share, &game_path);
+    
+    trash::trash_game(&app_handle, &game_path)
 }
 
 #[tauri::command]
-fn open_in_cursor(folder_path: String) -> Result<(), String> {
-    let path = PathBuf::from(&folder_path);
+fn list_trash(app_handle: tauri::AppHandle) -> Result<Vec<trash::TrashEntry>, GroveError> {
+    trash::purge_old_trash(&app_handle)?;
+    trash::list_trash(&app_handle)
+}
+
+#[tauri::command]
+fn restore_from_trash(id: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    trash::restore_from_trash(&app_handle, &id)
+}
+
+#[tauri::command]
+fn empty_trash(app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::require_parent(&app_handle)?;
+    trash::empty_trash(&app_handle)
+}
+
+#[tauri::command]
+fn list_snapshots(game_path: String, app_handle: tauri::AppHandle) -> Result<Vec<snapshots::Snapshot>, GroveError> {
+    let path = PathBuf::from(&game_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
     
     // Check if the directory exists
     if !path.exists() {
-        return Err(format!("Directory does not exist: {}", folder_path));
+        return Err(GroveError::not_found(&path));
     }
     
     if !path.is_dir() {
-        return Err(format!("Path is not a directory: {}", folder_path));
+        return Err(GroveError::not_a_directory(&path));
     }
     
-    // On macOS, use the 'open' command with Cursor
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg("-a")
-            .arg("Cursor")
-            .arg(&folder_path)
-            .spawn()
-            .map_err(|e| format!("Failed to open Cursor: {}", e))?;
+    snapshots::list_snapshots(&path)
+}
+
+#[tauri::command]
+fn restore_snapshot(game_path: String, snapshot_id: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    let path = PathBuf::from(&game_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
     }
     
-    // On Windows, try to use cursor.exe or code.exe
-    #[cfg(target_os = "windows")]
-    {
-        // Try Cursor first, then fall back to VS Code
-        let result = Command::new("cursor")
-            .arg(&folder_path)
-            .spawn();
-        
-        if result.is_err() {
-            Command::new("code")
-                .arg(&folder_path)
-                .spawn()
-                .map_err(|e| format!("Failed to open Cursor/Code: {}", e))?;
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    snapshots::restore_snapshot(&path, &snapshot_id)
+}
+
+#[derive(Serialize)]
+struct SnapshotPreview {
+    url: String,
+    path: String, // Pass to stop_game_server when the preview is closed
+}
+
+#[tauri::command]
+fn preview_snapshot(game_path: String, snapshot_id: String, app_handle: tauri::AppHandle, servers: tauri::State<server::GameServers>) -> Result<SnapshotPreview, GroveError> {
+    let path = PathBuf::from(&game_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    let preview_path = snapshots::export_snapshot(&path, &snapshot_id)?;
+    let url = serve_folder(preview_path.clone(), &servers)?;
+    open_url(&url)?;
+    
+    Ok(SnapshotPreview {
+        url,
+        path: preview_path.to_string_lossy().to_string(),
+    })
+}
+
+#[tauri::command]
+fn list_templates(app_handle: tauri::AppHandle) -> Vec<templates::TemplateEntry> {
+    templates::list_templates(&app_handle)
+}
+
+#[tauri::command]
+fn save_as_template(game_path: String, template_name: String, app_handle: tauri::AppHandle) -> Result<templates::TemplateEntry, GroveError> {
+    parental::require_parent(&app_handle)?;
+    
+    let path = PathBuf::from(&game_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    templates::save_as_template(&app_handle, &path, &template_name)
+}
+
+#[tauri::command]
+fn list_editors() -> Vec<editors::Editor> {
+    editors::list_editors()
+}
+
+// Saved on the active profile, or for the grown-up view when there's none
+#[tauri::command]
+fn set_preferred_editor(editor_id: String, app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    if !editors::is_known_editor(&editor_id) {
+        return Err(GroveError::EditorNotInstalled { editor: editor_id });
+    }
+    
+    match profiles::active_profile(&app_handle) {
+        Some(profile) => {
+            let changes = profiles::ProfileChanges {
+                editor: Some(editor_id),
+                ..Default::default()
+            };
+            profiles::update_profile(&app_handle, &profile.id, changes)?;
+            Ok(())
         }
+        None => settings::set_preferred_editor(&app_handle, &editor_id),
     }
+}
+
+// `file` is relative to the game folder, `editor_id` defaults to the
+// player's preferred editor
+#[tauri::command]
+fn open_in_editor(
+    path: String,
+    editor_id: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), GroveError> {
+    let game_path = PathBuf::from(&path);
     
-    // On Linux, try cursor or code command
-    #[cfg(target_os = "linux")]
-    {
-        let result = Command::new("cursor")
-            .arg(&folder_path)
-            .spawn();
-        
-        if result.is_err() {
-            Command::new("code")
-                .arg(&folder_path)
-                .spawn()
-                .map_err(|e| format!("Failed to open Cursor/Code: {}", e))?;
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &game_path)?;
+    
+    // Check if the directory exists
+    if !game_path.exists() {
+        return Err(GroveError::not_found(&game_path));
+    }
+    
+    if !game_path.is_dir() {
+        return Err(GroveError::not_a_directory(&game_path));
+    }
+    
+    // The file has to be inside the game too
+    let file_path = match &file {
+        Some(file) => {
+            let game_path = game_path.canonicalize()
+                .map_err(|_| GroveError::not_found(&game_path))?;
+            let file_path = game_path.join(file).canonicalize()
+                .map_err(|_| GroveError::not_found(&game_path.join(file)))?;
+            if !file_path.starts_with(&game_path) {
+                return Err(GroveError::OutsideGamesRoot { path: file_path.to_string_lossy().to_string() });
+            }
+            Some(file_path)
         }
+        None => None,
+    };
+    
+    let editor_id = editor_id
+        .or_else(|| profiles::active_profile(&app_handle).and_then(|profile| profile.editor))
+        .or_else(|| settings::preferred_editor(&app_handle))
+        .unwrap_or_else(|| editors::default_editor_id().to_string());
+    
+    editors::open_in_editor(&editor_id, &game_path, file_path.as_deref(), line)
+}
+
+// `out_path` is where the family chose to save the file, usually from a save dialog
+#[tauri::command]
+fn export_single_html(game_path: String, out_path: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    let path = PathBuf::from(&game_path);
+    let out_path = PathBuf::from(&out_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
     }
     
-    Ok(())
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    if !out_path.parent().is_some_and(|parent| parent.is_dir()) {
+        return Err(GroveError::not_found(&out_path));
+    }
+    
+    export::export_single_html(&path, &out_path)?;
+    
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn export_game_zip(game_path: String, out_path: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    let path = PathBuf::from(&game_path);
+    let out_path = PathBuf::from(&out_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    if !out_path.parent().is_some_and(|parent| parent.is_dir()) {
+        return Err(GroveError::not_found(&out_path));
+    }
+    
+    archive::export_game_zip(&path, &out_path)?;
+    
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn import_game_zip(zip_path: String, library_root: String, app_handle: tauri::AppHandle) -> Result<String, GroveError> {
+    let zip_path = PathBuf::from(&zip_path);
+    let root = PathBuf::from(&library_root);
+    
+    // Imported games can only land directly inside a games root
+    validation::ensure_games_root(&app_handle, &root)?;
+    
+    // Check if the directory exists
+    if !root.exists() {
+        return Err(GroveError::not_found(&root));
+    }
+    
+    if !root.is_dir() {
+        return Err(GroveError::not_a_directory(&root));
+    }
+    
+    if !zip_path.is_file() {
+        return Err(GroveError::not_found(&zip_path));
+    }
+    
+    let game_path = archive::import_game_zip(&zip_path, &root)?;
+    
+    Ok(game_path.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-fn open_html_in_browser(folder_path: String) -> Result<(), String> {
+fn open_html_in_browser(folder_path: String, app_handle: tauri::AppHandle, servers: tauri::State<server::GameServers>) -> Result<String, GroveError> {
     let path = PathBuf::from(&folder_path);
     
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
     // Check if the directory exists
     if !path.exists() {
-        return Err(format!("Directory does not exist: {}", folder_path));
+        return Err(GroveError::not_found(&path));
     }
     
     if !path.is_dir() {
-        return Err(format!("Path is not a directory: {}", folder_path));
+        return Err(GroveError::not_a_directory(&path));
     }
     
     // Build path to index.html
@@ -343,44 +911,160 @@ fn open_html_in_browser(folder_path: String) -> Result<(), String> {
     
     // Check if index.html exists
     if !html_path.exists() {
-        return Err(format!("index.html not found in: {}", folder_path));
+        return Err(GroveError::MissingIndexHtml { path: folder_path });
+    }
+    
+    // Every press of Play saves a snapshot the kid can go back to later
+    if let Err(e) = snapshots::take_snapshot(&path, "Played the game") {
+        println!("Failed to snapshot game: {}", e);
     }
     
-    // Convert to file:// URL
-    let file_url = format!("file://{}", html_path.to_string_lossy());
+    let url = serve_folder(path, &servers)?;
+    open_url(&url)?;
+    
+    Ok(url)
+}
+
+// Reuses the folder's server if it's already running, otherwise starts one
+fn serve_folder(path: PathBuf, servers: &server::GameServers) -> Result<String, GroveError> {
+    let mut servers = servers.0.lock().map_err(|_| GroveError::internal("Game server state is poisoned"))?;
+    match servers.get(&path) {
+        Some(server) => Ok(server.url().to_string()),
+        None => {
+            let server = server::GameServer::start(path.clone())?;
+            let url = server.url().to_string();
+            servers.insert(path, server);
+            Ok(url)
+        }
+    }
+}
+
+#[tauri::command]
+fn stop_game_server(folder_path: String, servers: tauri::State<server::GameServers>) -> Result<(), GroveError> {
+    let path = PathBuf::from(&folder_path);
     
+    let mut servers = servers.0.lock().map_err(|_| GroveError::internal("Game server state is poisoned"))?;
+    if let Some(server) = servers.remove(&path) {
+        server.stop();
+        // Snapshot previews are temporary copies, they go with their server
+        snapshots::remove_preview(&path);
+    }
+    
+    Ok(())
+}
+
+#[tauri::command]
+fn start_lan_share(
+    game_path: String,
+    port: Option<u16>,
+    minutes: Option<u64>,
+    app_handle: tauri::AppHandle,
+    lan_share: tauri::State<server::LanShare>,
+) -> Result<share::LanShareInfo, GroveError> {
+    // Other devices on the network can see the game, so a grown-up says yes
+    parental::require_parent(&app_handle)?;
+    
+    let path = PathBuf::from(&game_path);
+    
+    // Paths come from the webview, only games in the games folder are allowed
+    validation::ensure_game_in_games_root(&app_handle, &path)?;
+    
+    // Check if the directory exists
+    if !path.exists() {
+        return Err(GroveError::not_found(&path));
+    }
+    
+    if !path.is_dir() {
+        return Err(GroveError::not_a_directory(&path));
+    }
+    
+    if !path.join("index.html").exists() {
+        return Err(GroveError::MissingIndexHtml { path: game_path });
+    }
+    
+    let mut lan_share = lan_share.0.lock().map_err(|_| GroveError::internal("LAN share state is poisoned"))?;
+    
+    // Only one game is shared at a time, and it may want the same port
+    if let Some(mut server) = lan_share.take() {
+        server.stop();
+    }
+    
+    let server = share::start_share(path, port, minutes)?;
+    let info = share::share_info(&server)?;
+    *lan_share = Some(server);
+    
+    Ok(info)
+}
+
+// Nothing once the share was stopped or timed out
+#[tauri::command]
+fn get_lan_share(lan_share: tauri::State<server::LanShare>) -> Result<Option<share::LanShareInfo>, GroveError> {
+    let mut lan_share = lan_share.0.lock().map_err(|_| GroveError::internal("LAN share state is poisoned"))?;
+    
+    if !lan_share.as_ref().is_some_and(|server| server.is_running()) {
+        if let Some(mut server) = lan_share.take() {
+            server.stop();
+        }
+        return Ok(None);
+    }
+    
+    lan_share.as_ref().map(share::share_info).transpose()
+}
+
+#[tauri::command]
+fn stop_lan_share(lan_share: tauri::State<server::LanShare>) -> Result<(), GroveError> {
+    let mut lan_share = lan_share.0.lock().map_err(|_| GroveError::internal("LAN share state is poisoned"))?;
+    if let Some(mut server) = lan_share.take() {
+        server.stop();
+    }
+    
+    Ok(())
+}
+
+// Stops sharing a game that is about to move or disappear
+fn stop_lan_share_of(lan_share: &server::LanShare, game_path: &Path) {
+    if let Ok(mut lan_share) = lan_share.0.lock() {
+        if lan_share.as_ref().is_some_and(|server| server.game_path() == game_path) {
+            if let Some(mut server) = lan_share.take() {
+                server.stop();
+            }
+        }
+    }
+}
+
+fn open_url(url: &str) -> Result<(), GroveError> {
     // Open in default browser using the 'open' command on macOS
     #[cfg(target_os = "macos")]
     {
         Command::new("open")
-            .arg(&file_url)
+            .arg(url)
             .spawn()
-            .map_err(|e| format!("Failed to open browser: {}", e))?;
+            .map_err(|e| GroveError::io("open_browser", e))?;
     }
     
     // On Windows, use 'start' command
     #[cfg(target_os = "windows")]
     {
         Command::new("cmd")
-            .args(&["/C", "start", "", &file_url])
+            .args(&["/C", "start", "", url])
             .spawn()
-            .map_err(|e| format!("Failed to open browser: {}", e))?;
+            .map_err(|e| GroveError::io("open_browser", e))?;
     }
     
     // On Linux, try xdg-open
     #[cfg(target_os = "linux")]
     {
         Command::new("xdg-open")
-            .arg(&file_url)
+            .arg(url)
             .spawn()
-            .map_err(|e| format!("Failed to open browser: {}", e))?;
+            .map_err(|e| GroveError::io("open_browser", e))?;
     }
     
     Ok(())
 }
 
 #[tauri::command]
-async fn check_for_updates_manually(app_handle: AppHandle) -> Result<String, String> {
+async fn check_for_updates_manually(app_handle: AppHandle) -> Result<String, GroveError> {
     #[cfg(desktop)]
     {
         use tauri_plugin_updater::UpdaterExt;
@@ -396,18 +1080,75 @@ async fn check_for_updates_manually(app_handle: AppHandle) -> Result<String, Str
                         }
                     }
                     Err(e) => {
-                        return Err(format!("Failed to check for updates: {}", e));
+                        return Err(GroveError::Updater { details: e.to_string() });
                     }
                 }
             }
             Err(e) => {
-                return Err(format!("Failed to get updater: {}", e));
+                return Err(GroveError::Updater { details: e.to_string() });
             }
         }
     }
     
     #[cfg(not(desktop))]
-    return Err("Update checking is not supported on this platform".to_string());
+    return Err(GroveError::Unsupported { feature: "Update checking".to_string() });
+}
+
+#[tauri::command]
+async fn install_update(app_handle: AppHandle) -> Result<(), GroveError> {
+    parental::require_parent(&app_handle)?;
+    
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_updater::UpdaterExt;
+        
+        let updater = app_handle.updater()
+            .map_err(|e| GroveError::Updater { details: e.to_string() })?;
+        let update = updater.check().await
+            .map_err(|e| GroveError::Updater { details: e.to_string() })?;
+        
+        if let Some(update) = update {
+            update.download_and_install(|_, _| {}, || {}).await
+                .map_err(|e| GroveError::Updater { details: e.to_string() })?;
+            
+            // Restart the app to apply the update
+            app_handle.restart();
+        }
+        
+        return Ok(());
+    }
+    
+    #[cfg(not(desktop))]
+    return Err(GroveError::Unsupported { feature: "Installing updates".to_string() });
+}
+
+#[tauri::command]
+fn has_parent_pin(app_handle: tauri::AppHandle) -> Result<bool, GroveError> {
+    Ok(parental::has_pin(&app_handle))
+}
+
+// Changing or removing an existing PIN needs the old one first
+#[tauri::command]
+fn set_parent_pin(pin: String, app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::require_parent(&app_handle)?;
+    parental::set_pin(&app_handle, &pin)
+}
+
+#[tauri::command]
+fn clear_parent_pin(app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::require_parent(&app_handle)?;
+    parental::clear_pin(&app_handle)
+}
+
+#[tauri::command]
+fn verify_parent_pin(pin: String, app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::verify_pin(&app_handle, &pin)
+}
+
+#[tauri::command]
+fn lock_parent_controls(app_handle: tauri::AppHandle) -> Result<(), GroveError> {
+    parental::lock(&app_handle);
+    Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -423,29 +1164,76 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(server::GameServers::default())
+        .manage(server::LanShare::default())
+        .manage(library::LibraryWatchers::default())
+        .manage(parental::ParentUnlock::default())
         .setup(|app| {
             // Create and set the menu
             let menu = menu::create_menu(&app.handle())?;
             app.set_menu(menu)?;
             
             // Initialize the store
-            let store = app.store("app_settings.json")?;
+            let store = app.store(settings::SETTINGS_STORE_FILE_NAME)?;
             
             // Optionally, set default values if they don't exist
-            if store.get("selected_games_path").is_none() {
-                store.set("selected_games_path".to_string(), json!(null));
+            if store.get(settings::SELECTED_GAMES_PATH_KEY).is_none() {
+                store.set(settings::SELECTED_GAMES_PATH_KEY.to_string(), json!(null));
+            }
+            
+            // Forget games that have been in the trash for too long
+            if let Err(e) = trash::purge_old_trash(&app.handle()) {
+                println!("Failed to purge trash: {}", e);
             }
             
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet, 
-            read_src_folders, 
+            read_library_games,
+            list_library_roots,
+            add_library_root,
+            remove_library_root,
+            list_profiles,
+            get_active_profile,
+            create_profile,
+            update_profile,
+            delete_profile,
+            switch_profile,
             read_folders_from_path, 
+            search_games,
+            watch_library,
+            unwatch_library,
             create_game_folder,
-            open_in_cursor,
+            duplicate_game,
+            rename_game,
+            trash_game,
+            list_trash,
+            restore_from_trash,
+            empty_trash,
+            list_snapshots,
+            restore_snapshot,
+            preview_snapshot,
+            list_templates,
+            save_as_template,
+            list_editors,
+            set_preferred_editor,
+            open_in_editor,
             open_html_in_browser,
-            check_for_updates_manually
+            export_single_html,
+            export_game_zip,
+            import_game_zip,
+            stop_game_server,
+            start_lan_share,
+            get_lan_share,
+            stop_lan_share,
+            check_for_updates_manually,
+            install_update,
+            has_parent_pin,
+            set_parent_pin,
+            clear_parent_pin,
+            verify_parent_pin,
+            lock_parent_controls
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+use crate::error::GroveError;
+use crate::files;
+
+// Libraries that starters load from a CDN are bundled with the app and copied
+// into each new game, so games keep working on a plane or behind a school
+// network that blocks CDNs.
+const LIBRARIES_MANIFEST_FILE_NAME: &str = "libraries.json";
+
+// Where a game keeps its copies, next to index.html
+pub const GAME_LIB_DIR_NAME: &str = "lib";
+
+#[derive(Deserialize, Clone)]
+pub struct Library {
+    pub id: String,
+    pub version: String,
+    pub file: String, // Relative to the bundled libs folder
+    pub cdn_urls: Vec<String>, // Script URLs the bundled file replaces
+}
+
+fn bundled_libraries(app_handle: &tauri::AppHandle) -> Option<(PathBuf, Vec<Library>)> {
+    files::bundled_dirs(app_handle, "libs").into_iter().find_map(|dir| {
+        let contents = fs::read_to_string(dir.join(LIBRARIES_MANIFEST_FILE_NAME)).ok()?;
+        let libraries = serde_json::from_str(&contents).ok()?;
+        Some((dir, libraries))
+    })
+}
+
+// Copies the bundled libraries a game's pages load from a CDN into its lib
+// folder and points the script tags at the copies. Libraries missing from the
+// bundle keep loading from the CDN.
+pub fn vendor_libraries(app_handle: &tauri::AppHandle, game_path: &Path) -> Result<(), GroveError> {
+    let (libs_dir, libraries) = match bundled_libraries(app_handle) {
+        Some(bundled) => bundled,
+        None => return Ok(()),
+    };
+
+    let mut pages = Vec::new();
+    collect_pages(game_path, &mut pages);
+
+    for page in pages {
+        let html = match fs::read_to_string(&page) {
+            Ok(html) => html,
+            Err(_) => continue,
+        };
+
+        let mut rewritten = html.clone();
+        for library in &libraries {
+            let url = match library.cdn_urls.iter().find(|url| html.contains(url.as_str())) {
+                Some(url) => url,
+                None => continue,
+            };
+
+            let source = libs_dir.join(&library.file);
+            let file_name = match source.file_name() {
+                Some(file_name) if source.is_file() => file_name.to_string_lossy().to_string(),
+                _ => {
+                    println!("Library {} {} is not bundled, keeping the CDN", library.id, library.version);
+                    continue;
+                }
+            };
+
+            let lib_dir = game_path.join(GAME_LIB_DIR_NAME);
+            let target = lib_dir.join(&file_name);
+            if !target.exists() {
+                fs::create_dir_all(&lib_dir)
+                    .map_err(|e| GroveError::io("create_lib_folder", e))?;
+                fs::copy(&source, &target)
+                    .map_err(|e| GroveError::io("copy_library", e))?;
+            }
+
+            rewritten = rewritten.replace(url.as_str(), &local_src(game_path, &page, &file_name));
+        }
+
+        if rewritten != html {
+            fs::write(&page, rewritten)
+                .map_err(|e| GroveError::io("update_script_tags", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// "lib/three.min.js" from index.html, "../lib/three.min.js" one folder down
+fn local_src(game_path: &Path, page: &Path, file_name: &str) -> String {
+    let depth = page
+        .parent()
+        .and_then(|dir| dir.strip_prefix(game_path).ok())
+        .map_or(0, |relative| relative.components().count());
+
+    format!("{}{}/{}", "../".repeat(depth), GAME_LIB_DIR_NAME, file_name)
+}
+
+fn collect_pages(dir: &Path, found: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        if files::is_ignored_name(&entry.file_name()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let is_page = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+
+        // Symlinks are skipped, they can loop or lead out of the game
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_pages(&path, found),
+            Ok(file_type) if file_type.is_file() && is_page => found.push(path),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use notify_debouncer_full::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::GroveError;
+use crate::{files, folder_entry, FolderEntry};
+
+pub const GAME_ADDED_EVENT: &str = "game-added";
+pub const GAME_REMOVED_EVENT: &str = "game-removed";
+pub const GAME_RENAMED_EVENT: &str = "game-renamed";
+pub const GAME_MODIFIED_EVENT: &str = "game-modified";
+
+#[derive(Serialize, Clone)]
+struct GameRenamed {
+    old_path: String,
+    old_name: String,
+    game: FolderEntry,
+}
+
+// Newest-file times are expensive to compute for big libraries, so they're
+// cached for games inside watched libraries, where the watcher can tell when
+// an entry goes stale. Unwatched games are always computed fresh.
+#[derive(Default)]
+struct LastModifiedCache {
+    watched_roots: HashSet<PathBuf>,
+    times: HashMap<PathBuf, u64>,
+}
+
+static LAST_MODIFIED_CACHE: OnceLock<Mutex<LastModifiedCache>> = OnceLock::new();
+
+fn last_modified_cache() -> &'static Mutex<LastModifiedCache> {
+    LAST_MODIFIED_CACHE.get_or_init(|| Mutex::new(LastModifiedCache::default()))
+}
+
+pub fn last_modified(game_path: &Path) -> u64 {
+    let is_watched = {
+        let cache = match last_modified_cache().lock() {
+            Ok(cache) => cache,
+            Err(_) => return files::newest_modified(game_path),
+        };
+        if let Some(time) = cache.times.get(game_path) {
+            return *time;
+        }
+        game_path.parent().is_some_and(|root| cache.watched_roots.contains(root))
+    };
+
+    // Computed without holding the lock, the walk can take a while
+    let time = files::newest_modified(game_path);
+    if is_watched {
+        if let Ok(mut cache) = last_modified_cache().lock() {
+            cache.times.insert(game_path.to_path_buf(), time);
+        }
+    }
+    time
+}
+
+fn invalidate_last_modified(game_path: &Path) {
+    if let Ok(mut cache) = last_modified_cache().lock() {
+        cache.times.remove(game_path);
+    }
+}
+
+// A running library watcher. Dropping it stops watching and forgets the
+// cached times for that library.
+pub struct LibraryWatch {
+    root: PathBuf,
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+}
+
+impl Drop for LibraryWatch {
+    fn drop(&mut self) {
+        if let Ok(mut cache) = last_modified_cache().lock() {
+            cache.watched_roots.remove(&self.root);
+            let root = &self.root;
+            cache.times.retain(|path, _| path.parent() != Some(root.as_path()));
+        }
+    }
+}
+
+// Active watchers keyed by the library root they watch
+#[derive(Default)]
+pub struct LibraryWatchers(pub Mutex<HashMap<PathBuf, LibraryWatch>>);
+
+// Watches a games root and tells the frontend about games appearing,
+// disappearing, being renamed or being edited, wherever the change came from.
+pub fn watch_library(app_handle: AppHandle, root: PathBuf) -> Result<LibraryWatch, GroveError> {
+    let mut known_games = scan_library(&root);
+    let watched_root = root.clone();
+
+    let mut debouncer = new_debouncer(Duration::from_millis(500), None, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+
+        // Anything that changed inside a game folder. Reads show up as
+        // access events and don't count as changes.
+        let mut changed = HashSet::new();
+        for event in events.iter().filter(|event| !event.kind.is_access()) {
+            for path in &event.paths {
+                if let Some(game_path) = changed_game(&watched_root, path) {
+                    changed.insert(game_path);
+                }
+            }
+        }
+
+        // Drop stale times before rescanning so the new entries are accurate
+        for game_path in &changed {
+            invalidate_last_modified(game_path);
+        }
+
+        let current_games = scan_library(&watched_root);
+
+        let mut added: Vec<&FolderEntry> = current_games
+            .iter()
+            .filter(|(path, _)| !known_games.contains_key(*path))
+            .map(|(_, game)| game)
+            .collect();
+        let mut removed: Vec<&FolderEntry> = known_games
+            .iter()
+            .filter(|(path, _)| !current_games.contains_key(*path))
+            .map(|(_, game)| game)
+            .collect();
+
+        for (old, new) in match_renames(&mut removed, &mut added) {
+            let _ = app_handle.emit(GAME_RENAMED_EVENT, GameRenamed {
+                old_path: old.path.clone(),
+                old_name: old.name.clone(),
+                game: new.clone(),
+            });
+        }
+        for game in &added {
+            let _ = app_handle.emit(GAME_ADDED_EVENT, (*game).clone());
+        }
+        for game in &removed {
+            let _ = app_handle.emit(GAME_REMOVED_EVENT, (*game).clone());
+        }
+
+        // Only games that were already there, new ones were announced above
+        for game_path in changed.iter().filter(|path| known_games.contains_key(*path)) {
+            if let Some(game) = current_games.get(game_path) {
+                let _ = app_handle.emit(GAME_MODIFIED_EVENT, game.clone());
+            }
+        }
+
+        known_games = current_games;
+    })
+    .map_err(|e| GroveError::io("create_file_watcher", e))?;
+
+    debouncer
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| GroveError::io("watch_games_folder", e))?;
+
+    if let Ok(mut cache) = last_modified_cache().lock() {
+        cache.watched_roots.insert(root.clone());
+    }
+
+    Ok(LibraryWatch {
+        root,
+        _debouncer: debouncer,
+    })
+}
+
+fn scan_library(root: &Path) -> HashMap<PathBuf, FolderEntry> {
+    let mut games = HashMap::new();
+
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(game) = folder_entry(&path) {
+                games.insert(path, game);
+            }
+        }
+    }
+
+    games
+}
+
+// Pairs up games that vanished with games that appeared in the same batch.
+// The manifest's id identifies a game across renames; without manifests a
+// single disappearance plus a single appearance is a rename too.
+fn match_renames<'a>(removed: &mut Vec<&'a FolderEntry>, added: &mut Vec<&'a FolderEntry>) -> Vec<(&'a FolderEntry, &'a FolderEntry)> {
+    let mut renames = Vec::new();
+
+    let mut index = 0;
+    while index < removed.len() {
+        let id = removed[index].manifest.as_ref().map(|manifest| manifest.id.as_str());
+        let partner = id.and_then(|id| {
+            added.iter().position(|game| {
+                game.manifest.as_ref().map(|manifest| manifest.id.as_str()) == Some(id)
+            })
+        });
+
+        match partner {
+            Some(partner) => renames.push((removed.remove(index), added.remove(partner))),
+            None => index += 1,
+        }
+    }
+
+    if removed.len() == 1 && added.len() == 1 && removed[0].manifest.is_none() && added[0].manifest.is_none() {
+        renames.push((removed.remove(0), added.remove(0)));
+    }
+
+    renames
+}
+
+// Maps a changed path to the game folder it belongs to, skipping changes to
+// history, caches and other files that aren't part of the game.
+fn changed_game(root: &Path, changed_path: &Path) -> Option<PathBuf> {
+    let relative = changed_path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+
+    // Hidden folders next to the games aren't games
+    let game_name = match components.next()? {
+        Component::Normal(name) if !name.to_string_lossy().starts_with('.') => name,
+        _ => return None,
+    };
+
+    // The game folder itself being created or deleted is handled above
+    let mut inside = false;
+    for component in components {
+        inside = true;
+        if let Component::Normal(part) = component {
+            if files::is_ignored_name(part) {
+                return None;
+            }
+        }
+    }
+
+    if inside {
+        Some(root.join(game_name))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::error::GroveError;
+
+// Every game folder carries a game.json manifest so it keeps its identity
+// even when the folder itself gets renamed.
+pub const MANIFEST_FILE_NAME: &str = "game.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub version: String,
+}
+
+// The game a remix was copied from, found again by its id wherever it moved
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ParentGame {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameManifest {
+    // Stays the same through renames and moves, copies get a new one
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub game_type: String,
+    pub template: TemplateInfo,
+    pub created_at: u64, // Unix timestamp
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub remixed_from: Option<ParentGame>,
+}
+
+impl GameManifest {
+    // For games made before manifests existed, where the template is unknown
+    pub fn untemplated(name: &str) -> GameManifest {
+        GameManifest {
+            id: new_game_id(),
+            name: name.to_string(),
+            icon: None,
+            game_type: "unknown".to_string(),
+            template: TemplateInfo {
+                name: "unknown".to_string(),
+                version: String::new(),
+            },
+            created_at: now_unix(),
+            author: None,
+            remixed_from: None,
+        }
+    }
+}
+
+pub fn read_manifest(game_path: &Path) -> Option<GameManifest> {
+    let manifest_path = game_path.join(MANIFEST_FILE_NAME);
+
+    // Older games have no manifest, so a missing or broken file isn't an error
+    let contents = fs::read_to_string(&manifest_path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+pub fn write_manifest(game_path: &Path, manifest: &GameManifest) -> Result<(), GroveError> {
+    let manifest_path = game_path.join(MANIFEST_FILE_NAME);
+
+    let contents = serde_json::to_string_pretty(manifest)
+        .map_err(GroveError::internal)?;
+
+    fs::write(&manifest_path, contents)
+        .map_err(|e| GroveError::io("write_game_manifest", e))
+}
+
+pub fn new_game_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+pub fn now_unix() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,147 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+
+use crate::error::GroveError;
+use crate::{manifest, settings};
+
+// Kids can play and create freely, but anything that could wreck the setup
+// (trashing games, changing where games live, installing updates, editing
+// starters) needs a parent to type their PIN first.
+
+// Only the Argon2 hash is stored, it carries its own salt
+const PARENT_PIN_KEY: &str = "parent_pin";
+
+// Kept next to the PIN so restarting the app doesn't reset a lockout
+const FAILED_ATTEMPTS_KEY: &str = "pin_failed_attempts";
+const LOCKED_UNTIL_KEY: &str = "pin_locked_until"; // Unix timestamp
+
+pub const MIN_PIN_DIGITS: usize = 4;
+pub const MAX_PIN_DIGITS: usize = 8;
+
+// How long one correct PIN keeps grown-up actions unlocked
+const UNLOCK_DURATION: Duration = Duration::from_secs(5 * 60);
+
+// Guessing a four digit PIN should take longer than a rainy afternoon
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct UnlockState {
+    unlocked_at: Option<Instant>,
+}
+
+#[derive(Default)]
+pub struct ParentUnlock(Mutex<UnlockState>);
+
+fn stored_pin_hash(app_handle: &tauri::AppHandle) -> Option<String> {
+    let store = app_handle.store(settings::PROTECTED_STORE_FILE_NAME).ok()?;
+
+    store
+        .get(PARENT_PIN_KEY)
+        .and_then(|value| value.as_str().map(|hash| hash.to_string()))
+}
+
+pub fn has_pin(app_handle: &tauri::AppHandle) -> bool {
+    stored_pin_hash(app_handle).is_some()
+}
+
+pub fn set_pin(app_handle: &tauri::AppHandle, pin: &str) -> Result<(), GroveError> {
+    if pin.len() < MIN_PIN_DIGITS || pin.len() > MAX_PIN_DIGITS || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(GroveError::InvalidPin { min_digits: MIN_PIN_DIGITS, max_digits: MAX_PIN_DIGITS });
+    }
+
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map_err(GroveError::internal)?
+        .to_string();
+
+    let store = app_handle.store(settings::PROTECTED_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+    store.set(PARENT_PIN_KEY, hash);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))
+}
+
+pub fn clear_pin(app_handle: &tauri::AppHandle) -> Result<(), GroveError> {
+    let store = app_handle.store(settings::PROTECTED_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+    store.delete(PARENT_PIN_KEY);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))
+}
+
+pub fn verify_pin(app_handle: &tauri::AppHandle, pin: &str) -> Result<(), GroveError> {
+    let hash = match stored_pin_hash(app_handle) {
+        Some(hash) => hash,
+        None => return Ok(()), // No PIN set up, nothing to unlock
+    };
+
+    // Held for the whole check so guesses can't race each other
+    let unlock = app_handle.state::<ParentUnlock>();
+    let mut state = unlock.0.lock().map_err(|_| GroveError::internal("Parent unlock state is poisoned"))?;
+
+    let store = app_handle.store(settings::PROTECTED_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+
+    let now = manifest::now_unix();
+    let locked_until = store.get(LOCKED_UNTIL_KEY).and_then(|value| value.as_u64()).unwrap_or(0);
+    if locked_until > now {
+        return Err(GroveError::PinLocked { seconds: locked_until - now });
+    }
+
+    let parsed = PasswordHash::new(&hash).map_err(GroveError::internal)?;
+    if Argon2::default().verify_password(pin.as_bytes(), &parsed).is_ok() {
+        state.unlocked_at = Some(Instant::now());
+        store.delete(FAILED_ATTEMPTS_KEY);
+        store.delete(LOCKED_UNTIL_KEY);
+        return store.save()
+            .map_err(|e| GroveError::io("save_settings", e));
+    }
+
+    let failed_attempts = store.get(FAILED_ATTEMPTS_KEY)
+        .and_then(|value| value.as_u64())
+        .map_or(0, |attempts| attempts as u32)
+        + 1;
+    let error = if failed_attempts >= MAX_FAILED_ATTEMPTS {
+        store.delete(FAILED_ATTEMPTS_KEY);
+        store.set(LOCKED_UNTIL_KEY, now + LOCKOUT_DURATION.as_secs());
+        GroveError::PinLocked { seconds: LOCKOUT_DURATION.as_secs() }
+    } else {
+        store.set(FAILED_ATTEMPTS_KEY, failed_attempts);
+        GroveError::WrongPin { attempts_left: MAX_FAILED_ATTEMPTS - failed_attempts }
+    };
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))?;
+    Err(error)
+}
+
+// Locks grown-up actions again before the unlock runs out
+pub fn lock(app_handle: &tauri::AppHandle) {
+    if let Ok(mut state) = app_handle.state::<ParentUnlock>().0.lock() {
+        state.unlocked_at = None;
+    }
+}
+
+// Call at the top of every command a child shouldn't be able to run alone
+pub fn require_parent(app_handle: &tauri::AppHandle) -> Result<(), GroveError> {
+    if !has_pin(app_handle) {
+        return Ok(());
+    }
+
+    let unlock = app_handle.state::<ParentUnlock>();
+    let state = unlock.0.lock().map_err(|_| GroveError::internal("Parent unlock state is poisoned"))?;
+
+    match state.unlocked_at {
+        Some(unlocked_at) if unlocked_at.elapsed() < UNLOCK_DURATION => Ok(()),
+        _ => Err(GroveError::ParentPinRequired),
+    }
+}
@@ -0,0 +1,269 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::GroveError;
+use crate::validation;
+
+// Each child gets a profile with their own games folder. Profiles are kept
+// apart from the app settings so siblings can't pick each other's folders.
+pub const PROFILES_STORE_FILE_NAME: &str = "profiles.json";
+
+const PROFILES_KEY: &str = "profiles";
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
+// Id used when a name has nothing left after cleaning, like "🚀🚀🚀"
+const FALLBACK_PROFILE_ID: &str = "profile";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+// Decides how much the app explains and which starters it suggests
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum AgeBand {
+    #[serde(rename = "5-7")]
+    Early,
+    #[serde(rename = "8-10")]
+    Middle,
+    #[serde(rename = "11-12")]
+    Older,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub avatar: Option<String>, // An emoji or a path to a picture
+    pub games_root: String,
+    pub editor: Option<String>, // Editor id, None uses the first one installed
+    #[serde(default)]
+    pub theme: Theme,
+    pub age_band: AgeBand,
+}
+
+// Fields left out of an update stay as they are
+#[derive(Deserialize, Default)]
+pub struct ProfileChanges {
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    pub games_root: Option<String>,
+    pub editor: Option<String>,
+    pub theme: Option<Theme>,
+    pub age_band: Option<AgeBand>,
+}
+
+pub fn list_profiles(app_handle: &tauri::AppHandle) -> Vec<Profile> {
+    let store = match app_handle.store(PROFILES_STORE_FILE_NAME) {
+        Ok(store) => store,
+        Err(_) => return Vec::new(),
+    };
+
+    store
+        .get(PROFILES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_profiles(app_handle: &tauri::AppHandle, profiles: &[Profile]) -> Result<(), GroveError> {
+    let store = app_handle.store(PROFILES_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+
+    let value = serde_json::to_value(profiles)
+        .map_err(GroveError::internal)?;
+    store.set(PROFILES_KEY, value);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_profiles", e))
+}
+
+pub fn active_profile(app_handle: &tauri::AppHandle) -> Option<Profile> {
+    let store = app_handle.store(PROFILES_STORE_FILE_NAME).ok()?;
+    let id = store.get(ACTIVE_PROFILE_KEY)?.as_str()?.to_string();
+
+    list_profiles(app_handle)
+        .into_iter()
+        .find(|profile| profile.id == id)
+}
+
+// None switches back to the grown-up view that sees every library root
+pub fn set_active_profile(app_handle: &tauri::AppHandle, id: Option<&str>) -> Result<Option<Profile>, GroveError> {
+    let profile = match id {
+        Some(id) => Some(find_profile(app_handle, id)?),
+        None => None,
+    };
+
+    let store = app_handle.store(PROFILES_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+    match &profile {
+        Some(profile) => store.set(ACTIVE_PROFILE_KEY, profile.id.clone()),
+        None => {
+            store.delete(ACTIVE_PROFILE_KEY);
+        }
+    }
+
+    store.save()
+        .map_err(|e| GroveError::io("save_profiles", e))?;
+
+    Ok(profile)
+}
+
+fn find_profile(app_handle: &tauri::AppHandle, id: &str) -> Result<Profile, GroveError> {
+    list_profiles(app_handle)
+        .into_iter()
+        .find(|profile| profile.id == id)
+        .ok_or_else(|| GroveError::ProfileNotFound { id: id.to_string() })
+}
+
+pub fn create_profile(
+    app_handle: &tauri::AppHandle,
+    name: &str,
+    avatar: Option<String>,
+    games_root: &str,
+    editor: Option<String>,
+    theme: Option<Theme>,
+    age_band: AgeBand,
+) -> Result<Profile, GroveError> {
+    let mut profiles = list_profiles(app_handle);
+
+    let name = validation::validate_display_name(name)?;
+    let games_root = prepare_games_root(Path::new(games_root))?;
+    ensure_root_unshared(&profiles, &games_root, None)?;
+
+    let profile = Profile {
+        id: unique_profile_id(&profiles, &name),
+        name,
+        avatar,
+        games_root: games_root.to_string_lossy().to_string(),
+        editor,
+        theme: theme.unwrap_or_default(),
+        age_band,
+    };
+    profiles.push(profile.clone());
+    save_profiles(app_handle, &profiles)?;
+
+    Ok(profile)
+}
+
+pub fn update_profile(app_handle: &tauri::AppHandle, id: &str, changes: ProfileChanges) -> Result<Profile, GroveError> {
+    let mut profiles = list_profiles(app_handle);
+    let index = profiles.iter()
+        .position(|profile| profile.id == id)
+        .ok_or_else(|| GroveError::ProfileNotFound { id: id.to_string() })?;
+
+    if let Some(games_root) = changes.games_root {
+        let games_root = prepare_games_root(Path::new(&games_root))?;
+        ensure_root_unshared(&profiles, &games_root, Some(id))?;
+        profiles[index].games_root = games_root.to_string_lossy().to_string();
+    }
+
+    let profile = &mut profiles[index];
+    if let Some(name) = changes.name {
+        profile.name = validation::validate_display_name(&name)?;
+    }
+    if let Some(avatar) = changes.avatar {
+        profile.avatar = Some(avatar);
+    }
+    if let Some(editor) = changes.editor {
+        profile.editor = Some(editor);
+    }
+    if let Some(theme) = changes.theme {
+        profile.theme = theme;
+    }
+    if let Some(age_band) = changes.age_band {
+        profile.age_band = age_band;
+    }
+
+    let profile = profile.clone();
+    save_profiles(app_handle, &profiles)?;
+
+    Ok(profile)
+}
+
+// Only forgets the profile, the games in its folder stay where they are
+pub fn delete_profile(app_handle: &tauri::AppHandle, id: &str) -> Result<(), GroveError> {
+    let mut profiles = list_profiles(app_handle);
+    let count = profiles.len();
+    profiles.retain(|profile| profile.id != id);
+    if profiles.len() == count {
+        return Err(GroveError::ProfileNotFound { id: id.to_string() });
+    }
+
+    if active_profile(app_handle).is_some_and(|profile| profile.id == id) {
+        set_active_profile(app_handle, None)?;
+    }
+
+    save_profiles(app_handle, &profiles)
+}
+
+// A new child's folder usually doesn't exist yet
+fn prepare_games_root(path: &Path) -> Result<PathBuf, GroveError> {
+    if path.exists() && !path.is_dir() {
+        return Err(GroveError::not_a_directory(path));
+    }
+
+    fs::create_dir_all(path)
+        .map_err(|e| GroveError::io("create_games_folder", e))?;
+
+    path.canonicalize()
+        .map_err(|_| GroveError::not_found(path))
+}
+
+// Siblings sharing a folder, or one folder inside the other, would see each
+// other's games again
+fn ensure_root_unshared(profiles: &[Profile], games_root: &Path, except_id: Option<&str>) -> Result<(), GroveError> {
+    for profile in profiles {
+        if Some(profile.id.as_str()) == except_id {
+            continue;
+        }
+
+        let other_root = Path::new(&profile.games_root);
+        if games_root.starts_with(other_root) || other_root.starts_with(games_root) {
+            return Err(GroveError::GamesRootTaken {
+                path: games_root.to_string_lossy().to_string(),
+                profile: profile.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Turns "Space Cat! 🚀" into "space-cat": lowercase letters, digits and
+// single hyphens only
+fn profile_id_for(name: &str) -> String {
+    let mut id = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            id.push(c);
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+
+    let id = id.trim_matches('-');
+    if id.is_empty() {
+        FALLBACK_PROFILE_ID.to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+// "Sam" becomes "sam", a second Sam becomes "sam-2"
+fn unique_profile_id(profiles: &[Profile], name: &str) -> String {
+    let base = profile_id_for(name);
+    let mut id = base.clone();
+    let mut counter = 2;
+
+    while profiles.iter().any(|profile| profile.id == id) {
+        id = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+
+    id
+}
@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::Serialize;
+
+use crate::error::GroveError;
+use crate::{files, folder_entry, FolderEntry};
+
+// Any match on a game's name beats any match inside its files
+const NAME_MATCH_BONUS: i64 = 100_000;
+
+// Big files are almost always bundled libraries, not something a kid wrote
+const MAX_SEARCHED_FILE_SIZE: u64 = 512 * 1024;
+
+const SEARCHED_EXTENSIONS: &[&str] = &["html", "htm", "js", "mjs", "css", "json", "md", "txt"];
+
+// Characters of context shown on each side of a content match
+const SNIPPET_RADIUS: usize = 40;
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    Name,
+    Title,
+    Content,
+}
+
+// The text files of each searched game, kept until the game's last modified
+// time changes. That time comes from the library's cache, so searching a
+// watched library again reads nothing from disk that hasn't changed.
+struct IndexedGame {
+    last_modified: u64,
+    files: Vec<IndexedFile>,
+}
+
+struct IndexedFile {
+    relative: String,
+    contents: String,
+    lowercase: String,
+}
+
+static CONTENT_INDEX: OnceLock<Mutex<HashMap<PathBuf, IndexedGame>>> = OnceLock::new();
+
+fn content_index() -> &'static Mutex<HashMap<PathBuf, IndexedGame>> {
+    CONTENT_INDEX.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    game: FolderEntry,
+    score: i64,
+    matched: MatchKind,
+    snippet: String,
+    file: Option<String>, // Relative path of the file a content match was found in
+}
+
+pub fn search_games(root: &Path, query: &str) -> Result<Vec<SearchResult>, GroveError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(root)
+        .map_err(|e| GroveError::io("read_games_folder", e))?;
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let lowercase_query = query.to_lowercase();
+
+    let mut results = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in entries.flatten() {
+        let game = match folder_entry(&entry.path()) {
+            Some(game) => game,
+            None => continue,
+        };
+        seen.insert(entry.path());
+
+        // Files are only looked at when the name doesn't match already
+        let result = match_name(&matcher, &game, query)
+            .or_else(|| match_content(&entry.path(), game.last_modified, &lowercase_query));
+
+        if let Some((score, matched, snippet, file)) = result {
+            results.push(SearchResult { game, score, matched, snippet, file });
+        }
+    }
+
+    // Forget games that were removed from this library
+    if let Ok(mut index) = content_index().lock() {
+        index.retain(|path, _| path.parent() != Some(root) || seen.contains(path));
+    }
+
+    results.sort_by(|a, b| {
+        b.score.cmp(&a.score)
+            .then(b.game.last_modified.cmp(&a.game.last_modified))
+    });
+
+    Ok(results)
+}
+
+type Match = (i64, MatchKind, String, Option<String>);
+
+fn match_name(matcher: &SkimMatcherV2, game: &FolderEntry, query: &str) -> Option<Match> {
+    let name_match = matcher
+        .fuzzy_match(&game.name, query)
+        .map(|score| (score, MatchKind::Name, game.name.clone()));
+
+    let title_match = game.manifest.as_ref().and_then(|manifest| {
+        matcher
+            .fuzzy_match(&manifest.name, query)
+            .map(|score| (score, MatchKind::Title, manifest.name.clone()))
+    });
+
+    let best = match (name_match, title_match) {
+        (Some(name), Some(title)) => Some(if title.0 > name.0 { title } else { name }),
+        (name, title) => name.or(title),
+    };
+
+    best.map(|(score, matched, snippet)| (NAME_MATCH_BONUS + score, matched, snippet, None))
+}
+
+fn match_content(game_path: &Path, last_modified: u64, lowercase_query: &str) -> Option<Match> {
+    let mut index = content_index().lock().ok()?;
+
+    let is_fresh = index
+        .get(game_path)
+        .is_some_and(|indexed| indexed.last_modified == last_modified);
+    if !is_fresh {
+        index.insert(game_path.to_path_buf(), IndexedGame {
+            last_modified,
+            files: read_text_files(game_path),
+        });
+    }
+    let indexed = index.get(game_path)?;
+
+    let mut best: Option<(i64, String, String)> = None;
+    for file in &indexed.files {
+        if let Some((count, snippet)) = match_file(file, lowercase_query) {
+            // index.html is where most of a game lives, so it counts double
+            let count = if file.relative == "index.html" { count * 2 } else { count };
+            if best.as_ref().is_none_or(|(best_count, _, _)| count > *best_count) {
+                best = Some((count, snippet, file.relative.clone()));
+            }
+        }
+    }
+
+    best.map(|(count, snippet, file)| (count.min(NAME_MATCH_BONUS - 1), MatchKind::Content, snippet, Some(file)))
+}
+
+fn read_text_files(game_path: &Path) -> Vec<IndexedFile> {
+    let mut paths = Vec::new();
+    collect_text_files(game_path, &mut paths);
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            if !metadata.is_file() || metadata.len() > MAX_SEARCHED_FILE_SIZE {
+                return None;
+            }
+
+            let contents = fs::read_to_string(&path).ok()?;
+            let relative = path.strip_prefix(game_path).unwrap_or(&path);
+            Some(IndexedFile {
+                relative: relative.to_string_lossy().to_string(),
+                lowercase: contents.to_lowercase(),
+                contents,
+            })
+        })
+        .collect()
+}
+
+fn collect_text_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        if files::is_ignored_name(&entry.file_name()) {
+            continue;
+        }
+
+        // Symlinks are never followed, one pointing at its own folder would
+        // recurse forever
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_text_files(&path, found),
+            Ok(file_type) if file_type.is_file() && is_searched_file(&path) => found.push(path),
+            _ => {}
+        }
+    }
+}
+
+fn is_searched_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    SEARCHED_EXTENSIONS.contains(&extension.as_str())
+}
+
+// Returns how often the query occurs in the file and a snippet around the first hit
+fn match_file(file: &IndexedFile, lowercase_query: &str) -> Option<(i64, String)> {
+    let first = file.lowercase.find(lowercase_query)?;
+    let count = file.lowercase.matches(lowercase_query).count() as i64;
+
+    // Lowercasing can change byte offsets for some scripts, so only show the
+    // original text when the offsets still line up
+    let text = if file.lowercase.len() == file.contents.len() { &file.contents } else { &file.lowercase };
+
+    Some((count, snippet(text, first, lowercase_query.len())))
+}
+
+fn snippet(text: &str, start: usize, length: usize) -> String {
+    let mut from = start.saturating_sub(SNIPPET_RADIUS);
+    while !text.is_char_boundary(from) {
+        from -= 1;
+    }
+
+    let mut to = (start + length + SNIPPET_RADIUS).min(text.len());
+    while !text.is_char_boundary(to) {
+        to += 1;
+    }
+
+    let mut snippet = text[from..to].split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
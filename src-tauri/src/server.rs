@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use notify_debouncer_full::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+use crate::error::GroveError;
+use crate::{files, thumbnails};
+
+// Served pages subscribe to this endpoint and reload when a file changes
+const LIVE_RELOAD_PATH: &str = "/__grove/live-reload";
+
+const LIVE_RELOAD_SCRIPT: &str = "<script>new EventSource(\"/__grove/live-reload\").onmessage = () => location.reload();</script>";
+
+// Served pages post a picture of their canvas here once the game is running
+const THUMBNAIL_PATH: &str = "/__grove/thumbnail";
+
+// Copies the biggest canvas inside an animation frame, right after the game
+// drew it, because WebGL clears its canvas once the frame is shown.
+const THUMBNAIL_SCRIPT: &str = r#"<script>setTimeout(() => requestAnimationFrame(() => {
+  const source = [...document.querySelectorAll("canvas")].sort((a, b) => b.width * b.height - a.width * a.height)[0];
+  if (!source || !source.width || !source.height) return;
+  const thumbnail = document.createElement("canvas");
+  thumbnail.width = 480;
+  thumbnail.height = Math.round(480 * source.height / source.width);
+  thumbnail.getContext("2d").drawImage(source, 0, 0, thumbnail.width, thumbnail.height);
+  thumbnail.toBlob((png) => png && fetch("/__grove/thumbnail", { method: "POST", body: png }), "image/png");
+}), 3000);</script>"#;
+
+// Open live reload connections, each waiting for a message to forward
+type ReloadClients = Arc<Mutex<Vec<Sender<()>>>>;
+
+// A small static file server per game, so games run from a real http://
+// origin instead of file:// (which blocks ES modules, fetch() and audio).
+pub struct GameServer {
+    url: String,
+    server: Arc<Server>,
+    _watcher: Debouncer<RecommendedWatcher, RecommendedCache>,
+}
+
+impl GameServer {
+    pub fn start(root: PathBuf) -> Result<GameServer, GroveError> {
+        // Port 0 lets the OS pick a free port
+        let server = Server::http("127.0.0.1:0")
+            .map_err(|e| GroveError::io("start_game_server", e))?;
+
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .ok_or_else(|| GroveError::internal("Game server is not listening on a TCP port"))?;
+
+        let clients: ReloadClients = Arc::new(Mutex::new(Vec::new()));
+        let watcher = watch_for_changes(&root, clients.clone())?;
+
+        let server = Arc::new(server);
+        let worker = server.clone();
+        thread::spawn(move || {
+            // The iterator ends once the server is unblocked in stop()
+            for request in worker.incoming_requests() {
+                if request.url() == LIVE_RELOAD_PATH {
+                    let (sender, receiver) = mpsc::channel();
+                    if let Ok(mut clients) = clients.lock() {
+                        clients.push(sender);
+                    }
+                    // Event streams stay open, so don't block other requests
+                    thread::spawn(move || stream_reload_events(request, receiver));
+                } else if request.url() == THUMBNAIL_PATH && *request.method() == Method::Post {
+                    receive_thumbnail(&root, request);
+                } else {
+                    handle_request(&root, request);
+                }
+            }
+        });
+
+        Ok(GameServer {
+            url: format!("http://127.0.0.1:{}/", port),
+            server,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn stop(&self) {
+        self.server.unblock();
+    }
+}
+
+// Running servers keyed by the game folder they serve
+#[derive(Default)]
+pub struct GameServers(pub Mutex<HashMap<PathBuf, GameServer>>);
+
+// Serves one game to phones and tablets on the same network. Read-only:
+// no live reload, no thumbnails, nothing but GET, and hidden folders like
+// .git stay hidden. It shuts itself down once its time is up.
+pub struct LanServer {
+    game_path: PathBuf,
+    url: String,
+    address: SocketAddr,
+    expires_at: Instant,
+    stopped: Arc<AtomicBool>,
+    // The worker thread owns the server, so the port closes as soon as it exits
+    server: Weak<Server>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl LanServer {
+    pub fn start(game_path: PathBuf, address: IpAddr, port: u16, lifetime: Duration) -> Result<LanServer, GroveError> {
+        let server = Server::http((address, port)).map_err(|e| {
+            let in_use = e
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::AddrInUse);
+            if in_use {
+                GroveError::PortInUse { port }
+            } else {
+                GroveError::io("start_lan_share", e)
+            }
+        })?;
+
+        // Port 0 lets the OS pick, so ask which one it got
+        let address = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| GroveError::internal("LAN server is not listening on a TCP port"))?;
+
+        let root = game_path
+            .canonicalize()
+            .map_err(|_| GroveError::not_found(&game_path))?;
+        let expires_at = Instant::now() + lifetime;
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let server = Arc::new(server);
+        let handle = Arc::downgrade(&server);
+        let worker_stopped = stopped.clone();
+        let worker = thread::spawn(move || {
+            // recv_timeout returns nothing both when stop() unblocks it and
+            // when the time runs out
+            while !worker_stopped.load(Ordering::SeqCst) {
+                let remaining = expires_at.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+
+                match server.recv_timeout(remaining) {
+                    Ok(Some(request)) => handle_read_only_request(&root, request),
+                    Ok(None) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(LanServer {
+            game_path,
+            url: format!("http://{}/", address),
+            address,
+            expires_at,
+            stopped,
+            server: handle,
+            worker: Some(worker),
+        })
+    }
+
+    pub fn game_path(&self) -> &Path {
+        &self.game_path
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    // False once stopped or timed out
+    pub fn is_running(&self) -> bool {
+        self.server.strong_count() > 0 && !self.remaining().is_zero()
+    }
+
+    // Waits for the worker, so the port is free again when this returns
+    pub fn stop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        if let Some(server) = self.server.upgrade() {
+            server.unblock();
+        }
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+
+        // tiny_http closes the socket on its own thread a moment after the
+        // server is dropped
+        for _ in 0..20 {
+            if TcpListener::bind(self.address).is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+// The one game shared on the network, if any
+#[derive(Default)]
+pub struct LanShare(pub Mutex<Option<LanServer>>);
+
+fn watch_for_changes(root: &Path, clients: ReloadClients) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, GroveError> {
+    let watched_root = root.to_path_buf();
+
+    // Editors write files in several steps, so wait for things to settle
+    let mut debouncer = new_debouncer(Duration::from_millis(200), None, move |result: DebounceEventResult| {
+        let events = match result {
+            Ok(events) => events,
+            Err(_) => return,
+        };
+
+        // Serving a file reads it, which mustn't trigger another reload
+        let relevant = events.iter().filter(|event| !event.kind.is_access()).any(|event| {
+            event.paths.iter().any(|path| {
+                let relative = path.strip_prefix(&watched_root).unwrap_or(path);
+                !is_ignored_path(relative)
+            })
+        });
+        if !relevant {
+            return;
+        }
+
+        // Drop clients whose browser tab has gone away
+        if let Ok(mut clients) = clients.lock() {
+            clients.retain(|client| client.send(()).is_ok());
+        }
+    })
+    .map_err(|e| GroveError::io("create_file_watcher", e))?;
+
+    debouncer
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|e| GroveError::io("watch_game_folder", e))?;
+
+    Ok(debouncer)
+}
+
+// Changes inside .git, .grove or node_modules never affect the page
+fn is_ignored_path(path: &Path) -> bool {
+    path.components().any(|component| match component {
+        Component::Normal(part) => files::is_ignored_name(part),
+        _ => false,
+    })
+}
+
+fn stream_reload_events(request: Request, receiver: Receiver<()>) {
+    let mut writer = request.into_writer();
+
+    let headers = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n";
+    if writer.write_all(headers.as_bytes()).and_then(|_| writer.flush()).is_err() {
+        return;
+    }
+
+    // Ends when the browser disconnects or the server is stopped
+    while receiver.recv().is_ok() {
+        if writer.write_all(b"data: reload\n\n").and_then(|_| writer.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+fn receive_thumbnail(root: &Path, mut request: Request) {
+    let mut png = Vec::new();
+    let limit = thumbnails::MAX_THUMBNAIL_SIZE as u64 + 1;
+    let status = match request.as_reader().take(limit).read_to_end(&mut png) {
+        Ok(_) => match thumbnails::save_thumbnail(root, &png) {
+            Ok(()) => 204,
+            Err(_) => 400,
+        },
+        Err(_) => 400,
+    };
+
+    let _ = request.respond(Response::empty(StatusCode(status)));
+}
+
+fn handle_request(root: &Path, request: Request) {
+    let file_path = match resolve_request_path(root, request.url()) {
+        Some(path) => path,
+        None => {
+            let _ = request.respond(Response::from_string("Not found").with_status_code(StatusCode(404)));
+            return;
+        }
+    };
+
+    if content_type(&file_path).starts_with("text/html") {
+        respond_with_html(&file_path, request);
+        return;
+    }
+
+    respond_with_file(&file_path, request);
+}
+
+fn handle_read_only_request(root: &Path, request: Request) {
+    if *request.method() != Method::Get && *request.method() != Method::Head {
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(StatusCode(405)));
+        return;
+    }
+
+    match resolve_shared_path(root, request.url()) {
+        Some(file_path) => respond_with_file(&file_path, request),
+        None => {
+            let _ = request.respond(Response::from_string("Not found").with_status_code(StatusCode(404)));
+        }
+    }
+}
+
+fn respond_with_file(file_path: &Path, request: Request) {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(_) => {
+            let _ = request.respond(Response::from_string("Not found").with_status_code(StatusCode(404)));
+            return;
+        }
+    };
+
+    let mut response = Response::from_file(file);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type(file_path)) {
+        response.add_header(header);
+    }
+    // Kids edit files constantly, never let the browser serve a stale copy
+    if let Ok(header) = Header::from_bytes("Cache-Control", "no-store") {
+        response.add_header(header);
+    }
+
+    let _ = request.respond(response);
+}
+
+fn respond_with_html(file_path: &Path, request: Request) {
+    let html = match fs::read_to_string(file_path) {
+        Ok(html) => html,
+        Err(_) => {
+            let _ = request.respond(Response::from_string("Not found").with_status_code(StatusCode(404)));
+            return;
+        }
+    };
+
+    let mut response = Response::from_string(inject_scripts(&html));
+    if let Ok(header) = Header::from_bytes("Content-Type", "text/html; charset=utf-8") {
+        response.add_header(header);
+    }
+    if let Ok(header) = Header::from_bytes("Cache-Control", "no-store") {
+        response.add_header(header);
+    }
+
+    let _ = request.respond(response);
+}
+
+fn inject_scripts(html: &str) -> String {
+    let scripts = format!("{}{}", LIVE_RELOAD_SCRIPT, THUMBNAIL_SCRIPT);
+
+    // Browsers run scripts after </body> too, so appending is a safe fallback
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(index) => format!("{}{}{}", &html[..index], scripts, &html[index..]),
+        None => format!("{}{}", html, scripts),
+    }
+}
+
+// Maps a request URL onto a file inside the game folder, refusing anything
+// that would escape it.
+fn resolve_request_path(root: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let decoded = percent_encoding::percent_decode_str(path).decode_utf8().ok()?;
+
+    let mut file_path = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => file_path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    if file_path.is_dir() {
+        file_path.push("index.html");
+    }
+
+    if file_path.is_file() {
+        Some(file_path)
+    } else {
+        None
+    }
+}
+
+// Like resolve_request_path, but other devices also can't see history, caches
+// or files a symbolic link pulls in from outside the game. `root` must be
+// canonical.
+fn resolve_shared_path(root: &Path, url: &str) -> Option<PathBuf> {
+    let file_path = resolve_request_path(root, url)?.canonicalize().ok()?;
+    let relative = file_path.strip_prefix(root).ok()?;
+
+    if is_ignored_path(relative) {
+        None
+    } else {
+        Some(file_path)
+    }
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "gltf" => "model/gltf+json",
+        "glb" => "model/gltf-binary",
+        "obj" => "text/plain; charset=utf-8",
+        "wasm" => "application/wasm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+use crate::error::GroveError;
+use crate::profiles;
+
+// Shared with the frontend, which reads and writes the same store file
+pub const SETTINGS_STORE_FILE_NAME: &str = "app_settings.json";
+
+// Only ever written by the backend: the library roots and the parent PIN that
+// guards them. The webview has no store permission, so it can't change them.
+pub const PROTECTED_STORE_FILE_NAME: &str = "protected_settings.json";
+
+pub const SELECTED_GAMES_PATH_KEY: &str = "selected_games_path";
+
+pub const LIBRARY_ROOTS_KEY: &str = "library_roots";
+
+// Editor for the grown-up view, profiles keep their own
+pub const PREFERRED_EDITOR_KEY: &str = "preferred_editor";
+
+pub const DEFAULT_ROOT_COLOR: &str = "#4caf50";
+
+// A folder games live in, like one per child or one for school projects
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LibraryRoot {
+    pub path: String,
+    pub label: String,
+    pub color: String,
+}
+
+// The roots the current player can see. A child only ever sees their own
+// profile's folder, grown-ups see every stored root.
+pub fn library_roots(app_handle: &tauri::AppHandle) -> Vec<LibraryRoot> {
+    match profiles::active_profile(app_handle) {
+        Some(profile) => vec![LibraryRoot {
+            path: profile.games_root,
+            label: profile.name,
+            color: DEFAULT_ROOT_COLOR.to_string(),
+        }],
+        None => stored_library_roots(app_handle),
+    }
+}
+
+pub fn stored_library_roots(app_handle: &tauri::AppHandle) -> Vec<LibraryRoot> {
+    let store = match app_handle.store(PROTECTED_STORE_FILE_NAME) {
+        Ok(store) => store,
+        Err(_) => return Vec::new(),
+    };
+
+    // Only roots added through add_library_root count, the folder picked in
+    // the UI lives in the webview's own settings and is never trusted
+    store.get(LIBRARY_ROOTS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_library_roots(app_handle: &tauri::AppHandle, roots: &[LibraryRoot]) -> Result<(), GroveError> {
+    let store = app_handle.store(PROTECTED_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+
+    let value = serde_json::to_value(roots)
+        .map_err(GroveError::internal)?;
+    store.set(LIBRARY_ROOTS_KEY, value);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))
+}
+
+pub fn selected_games_path(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    let store = app_handle.store(SETTINGS_STORE_FILE_NAME).ok()?;
+
+    store
+        .get(SELECTED_GAMES_PATH_KEY)
+        .and_then(|value| value.as_str().map(PathBuf::from))
+}
+
+pub fn preferred_editor(app_handle: &tauri::AppHandle) -> Option<String> {
+    let store = app_handle.store(SETTINGS_STORE_FILE_NAME).ok()?;
+
+    store
+        .get(PREFERRED_EDITOR_KEY)
+        .and_then(|value| value.as_str().map(|id| id.to_string()))
+}
+
+pub fn set_preferred_editor(app_handle: &tauri::AppHandle, editor_id: &str) -> Result<(), GroveError> {
+    let store = app_handle.store(SETTINGS_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+    store.set(PREFERRED_EDITOR_KEY, editor_id);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))
+}
+
+// Forgets the folder picked in the UI when that folder stops being a root
+pub fn clear_selected_games_path(app_handle: &tauri::AppHandle, path: &Path) -> Result<(), GroveError> {
+    if selected_games_path(app_handle).as_deref() != Some(path) {
+        return Ok(());
+    }
+
+    let store = app_handle.store(SETTINGS_STORE_FILE_NAME)
+        .map_err(GroveError::internal)?;
+    store.delete(SELECTED_GAMES_PATH_KEY);
+
+    store.save()
+        .map_err(|e| GroveError::io("save_settings", e))
+}
+
+// Folders the app is allowed to manage games in: every library root. With a
+// profile active only its own folder.
+pub fn games_roots(app_handle: &tauri::AppHandle) -> Vec<PathBuf> {
+    if let Some(profile) = profiles::active_profile(app_handle) {
+        return vec![PathBuf::from(profile.games_root)];
+    }
+
+    stored_library_roots(app_handle)
+        .into_iter()
+        .map(|root| PathBuf::from(root.path))
+        .collect()
+}
@@ -0,0 +1,101 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use qrcode::{Color, QrCode};
+use serde::Serialize;
+
+use crate::error::GroveError;
+use crate::server::LanServer;
+
+// Sharing on the network lets a friend's phone or a tablet on the same Wi-Fi
+// play a game by scanning a QR code.
+pub const DEFAULT_SHARE_PORT: u16 = 8787;
+
+// Long enough for a play session, short enough that a forgotten share closes
+pub const DEFAULT_SHARE_MINUTES: u64 = 30;
+pub const MAX_SHARE_MINUTES: u64 = 120;
+
+// Pixels per QR module, and the empty border scanners need around the code
+const QR_MODULE_SIZE: usize = 8;
+const QR_QUIET_ZONE: usize = 4;
+
+#[derive(Serialize, Clone)]
+pub struct LanShareInfo {
+    pub game_path: String,
+    pub url: String,
+    pub port: u16,
+    pub qr_code: String, // PNG data URI, usable as an <img> src
+    pub seconds_left: u64,
+}
+
+pub fn start_share(game_path: PathBuf, port: Option<u16>, minutes: Option<u64>) -> Result<LanServer, GroveError> {
+    let address = lan_address()?;
+    let minutes = minutes
+        .unwrap_or(DEFAULT_SHARE_MINUTES)
+        .clamp(1, MAX_SHARE_MINUTES);
+
+    LanServer::start(
+        game_path,
+        address,
+        port.unwrap_or(DEFAULT_SHARE_PORT),
+        Duration::from_secs(minutes * 60),
+    )
+}
+
+pub fn share_info(server: &LanServer) -> Result<LanShareInfo, GroveError> {
+    let png = qr_code_png(server.url())?;
+
+    Ok(LanShareInfo {
+        game_path: server.game_path().to_string_lossy().to_string(),
+        url: server.url().to_string(),
+        port: server.port(),
+        qr_code: format!("data:image/png;base64,{}", BASE64.encode(png)),
+        seconds_left: server.remaining().as_secs(),
+    })
+}
+
+// The address other devices reach this computer on, from the interface
+// that carries the default route
+fn lan_address() -> Result<IpAddr, GroveError> {
+    let address = local_ip_address::local_ip().map_err(|_| GroveError::NoNetwork)?;
+
+    if address.is_loopback() || address.is_unspecified() {
+        return Err(GroveError::NoNetwork);
+    }
+
+    Ok(address)
+}
+
+// Black modules on white, as a grayscale PNG
+fn qr_code_png(text: &str) -> Result<Vec<u8>, GroveError> {
+    let code = QrCode::new(text.as_bytes()).map_err(GroveError::internal)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+
+    let size = (modules + QR_QUIET_ZONE * 2) * QR_MODULE_SIZE;
+    let mut pixels = vec![255u8; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+
+        let left = (index % modules + QR_QUIET_ZONE) * QR_MODULE_SIZE;
+        let top = (index / modules + QR_QUIET_ZONE) * QR_MODULE_SIZE;
+        for y in top..top + QR_MODULE_SIZE {
+            pixels[y * size + left..y * size + left + QR_MODULE_SIZE].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(GroveError::internal)?;
+        writer.write_image_data(&pixels).map_err(GroveError::internal)?;
+    }
+
+    Ok(png)
+}
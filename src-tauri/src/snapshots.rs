@@ -0,0 +1,255 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use git2::build::CheckoutBuilder;
+use git2::{Commit, IndexAddOption, Oid, Repository, Signature};
+use serde::Serialize;
+
+use crate::error::GroveError;
+use crate::thumbnails::GROVE_DIR_NAME;
+
+// Every game has a git repository of its own, and a snapshot is simply a
+// commit. Kids never see git itself, only a timeline of snapshots to go back to.
+//
+// The repository lives in .grove/history with the game folder as its working
+// tree, so a game that is also a real project keeps its own .git untouched.
+
+const HISTORY_DIR_NAME: &str = "history";
+
+const SNAPSHOT_AUTHOR_NAME: &str = "Game Grove";
+const SNAPSHOT_AUTHOR_EMAIL: &str = "snapshots@game-grove.local";
+
+// Kept out of snapshots without adding a .gitignore to the game
+const EXCLUDED_PATTERNS: &str = ".git/\nnode_modules/\n.grove/\n.DS_Store\n";
+
+// Previews from this run share a folder with a random name, so nobody else
+// on the computer can guess it or put files in it ahead of time
+static PREVIEWS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+fn previews_dir() -> &'static Path {
+    PREVIEWS_DIR.get_or_init(|| {
+        std::env::temp_dir().join(format!("game-grove-previews-{}", uuid::Uuid::new_v4()))
+    })
+}
+
+#[derive(Serialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub time: u64, // Unix timestamp
+    pub summary: String,
+    pub changed_files: Vec<String>,
+}
+
+fn history_dir(game_path: &Path) -> PathBuf {
+    game_path.join(GROVE_DIR_NAME).join(HISTORY_DIR_NAME)
+}
+
+pub fn init_repository(game_path: &Path) -> Result<Repository, GroveError> {
+    let repo = Repository::init_bare(history_dir(game_path))
+        .map_err(GroveError::history)?;
+    repo.set_workdir(game_path, false)
+        .map_err(GroveError::history)?;
+
+    let exclude_path = repo.path().join("info").join("exclude");
+    if let Some(info_dir) = exclude_path.parent() {
+        fs::create_dir_all(info_dir)
+            .map_err(|e| GroveError::io("set_up_game_history", e))?;
+    }
+    fs::write(&exclude_path, EXCLUDED_PATTERNS)
+        .map_err(|e| GroveError::io("set_up_game_history", e))?;
+
+    Ok(repo)
+}
+
+// Copies of a game start a history of their own
+pub fn forget_history(game_path: &Path) -> Result<(), GroveError> {
+    let history = history_dir(game_path);
+    if !history.exists() {
+        return Ok(());
+    }
+
+    fs::remove_dir_all(&history)
+        .map_err(|e| GroveError::io("reset_game_history", e))
+}
+
+// The game's history, or None when no snapshot was ever taken
+fn open_history(game_path: &Path) -> Option<Repository> {
+    let repo = Repository::open_bare(history_dir(game_path)).ok()?;
+    // The working tree isn't stored, so it follows the game when it's renamed
+    repo.set_workdir(game_path, false).ok()?;
+    Some(repo)
+}
+
+fn open_repository(game_path: &Path) -> Result<Repository, GroveError> {
+    // Games made before snapshots existed get their history started on demand
+    match open_history(game_path) {
+        Some(repo) => Ok(repo),
+        None => init_repository(game_path),
+    }
+}
+
+// Commits the current state of the game. Returns the new snapshot id, or
+// None when nothing changed since the last snapshot.
+pub fn take_snapshot(game_path: &Path, summary: &str) -> Result<Option<String>, GroveError> {
+    let repo = open_repository(game_path)?;
+
+    let mut index = repo.index()
+        .map_err(GroveError::history)?;
+    index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+        .map_err(GroveError::history)?;
+    // Picks up deleted files too
+    index.update_all(["*"].iter(), None)
+        .map_err(GroveError::history)?;
+    index.write()
+        .map_err(GroveError::history)?;
+
+    let tree_id = index.write_tree()
+        .map_err(GroveError::history)?;
+    let tree = repo.find_tree(tree_id)
+        .map_err(GroveError::history)?;
+
+    let parent = head_commit(&repo);
+    if let Some(parent) = &parent {
+        if parent.tree_id() == tree_id {
+            return Ok(None);
+        }
+    }
+
+    let signature = Signature::now(SNAPSHOT_AUTHOR_NAME, SNAPSHOT_AUTHOR_EMAIL)
+        .map_err(GroveError::history)?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+
+    let commit_id = repo.commit(Some("HEAD"), &signature, &signature, summary, &tree, &parents)
+        .map_err(GroveError::history)?;
+
+    Ok(Some(commit_id.to_string()))
+}
+
+fn head_commit(repo: &Repository) -> Option<Commit<'_>> {
+    repo.head().ok()?.peel_to_commit().ok()
+}
+
+// Newest snapshot first
+pub fn list_snapshots(game_path: &Path) -> Result<Vec<Snapshot>, GroveError> {
+    let repo = match open_history(game_path) {
+        Some(repo) => repo,
+        None => return Ok(Vec::new()), // No history yet
+    };
+
+    if head_commit(&repo).is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo.revwalk()
+        .map_err(GroveError::history)?;
+    revwalk.push_head()
+        .map_err(GroveError::history)?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .map_err(GroveError::history)?;
+
+    let mut snapshots = Vec::new();
+    for commit_id in revwalk {
+        let commit_id = commit_id.map_err(GroveError::history)?;
+        let commit = repo.find_commit(commit_id)
+            .map_err(GroveError::history)?;
+
+        snapshots.push(Snapshot {
+            id: commit_id.to_string(),
+            time: commit.time().seconds().max(0) as u64,
+            summary: commit.summary().unwrap_or_default().to_string(),
+            changed_files: changed_files(&repo, &commit),
+        });
+    }
+
+    Ok(snapshots)
+}
+
+fn changed_files(repo: &Repository, commit: &Commit) -> Vec<String> {
+    let tree = match commit.tree() {
+        Ok(tree) => tree,
+        Err(_) => return Vec::new(),
+    };
+    let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+
+    let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
+        Ok(diff) => diff,
+        Err(_) => return Vec::new(),
+    };
+
+    diff.deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+fn find_snapshot<'repo>(repo: &'repo Repository, snapshot_id: &str) -> Result<Commit<'repo>, GroveError> {
+    Oid::from_str(snapshot_id)
+        .and_then(|oid| repo.find_commit(oid))
+        .map_err(|_| GroveError::SnapshotNotFound { snapshot_id: snapshot_id.to_string() })
+}
+
+// Puts the game back the way it was in an earlier snapshot. The current state
+// is saved first and the restore is recorded as a new snapshot, so nothing
+// later in the history is lost and going back can itself be undone.
+pub fn restore_snapshot(game_path: &Path, snapshot_id: &str) -> Result<String, GroveError> {
+    take_snapshot(game_path, "Before going back to an older version")?;
+
+    let repo = open_repository(game_path)?;
+    let snapshot = find_snapshot(&repo, snapshot_id)?;
+    let tree = snapshot.tree()
+        .map_err(GroveError::history)?;
+
+    // Files added after the snapshot are removed, ignored ones are left alone
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().remove_untracked(true);
+    repo.checkout_tree(tree.as_object(), Some(&mut checkout))
+        .map_err(GroveError::history)?;
+
+    let summary = format!("Went back to \"{}\"", snapshot.summary().unwrap_or_default());
+    match take_snapshot(game_path, &summary)? {
+        Some(id) => Ok(id),
+        // The game already looked exactly like the snapshot
+        None => head_commit(&repo)
+            .map(|commit| commit.id().to_string())
+            .ok_or_else(|| GroveError::history("no snapshot after going back")),
+    }
+}
+
+// Writes an old version of the game into a temporary folder so it can be
+// played without touching the live game.
+pub fn export_snapshot(game_path: &Path, snapshot_id: &str) -> Result<PathBuf, GroveError> {
+    let repo = open_history(game_path)
+        .ok_or_else(|| GroveError::SnapshotNotFound { snapshot_id: snapshot_id.to_string() })?;
+    let snapshot = find_snapshot(&repo, snapshot_id)?;
+    let tree = snapshot.tree()
+        .map_err(GroveError::history)?;
+
+    // A fresh folder every time, it's deleted again when the preview stops
+    let previews = previews_dir();
+    fs::create_dir_all(previews)
+        .map_err(|e| GroveError::io("create_preview_folder", e))?;
+    let preview_dir = previews.join(format!("{}-{}", snapshot.id(), uuid::Uuid::new_v4()));
+    fs::create_dir(&preview_dir)
+        .map_err(|e| GroveError::io("create_preview_folder", e))?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().target_dir(&preview_dir).update_index(false);
+    if let Err(e) = repo.checkout_tree(tree.as_object(), Some(&mut checkout)) {
+        let _ = fs::remove_dir_all(&preview_dir);
+        return Err(GroveError::history(e));
+    }
+
+    Ok(preview_dir)
+}
+
+// Deletes a preview once nothing serves it anymore. Anything that isn't a
+// preview from this run is left alone.
+pub fn remove_preview(path: &Path) {
+    if path.parent() != Some(previews_dir()) || path.file_name().is_none() {
+        return;
+    }
+
+    if let Err(e) = fs::remove_dir_all(path) {
+        println!("Failed to remove snapshot preview: {}", e);
+    }
+}
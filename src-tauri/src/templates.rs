@@ -0,0 +1,363 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::error::GroveError;
+use crate::{files, manifest, validation};
+
+// Each template folder describes itself with a template.json, so new
+// starters can be added by dropping a folder in place.
+pub const TEMPLATE_MANIFEST_FILE_NAME: &str = "template.json";
+
+// Files ending in .tmpl are always rendered and lose the extension
+pub const TEMPLATE_FILE_EXTENSION: &str = "tmpl";
+
+pub const DEFAULT_GAME_COLOR: &str = "#ffcc00";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub preview: Option<String>, // Image path relative to the template folder
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_game_type")]
+    pub game_type: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub render: Vec<String>, // Files to fill in with template variables
+}
+
+fn default_game_type() -> String {
+    "2d".to_string()
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Bundled,
+    User,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TemplateEntry {
+    pub id: String, // Folder name of the template
+    pub name: String,
+    pub description: String,
+    pub preview: Option<String>, // Absolute path to the preview image
+    pub tags: Vec<String>,
+    pub game_type: String,
+    pub version: Option<String>,
+    #[serde(skip_serializing)]
+    pub render: Vec<String>,
+    pub source: TemplateSource,
+    pub path: String,
+}
+
+pub fn user_templates_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, GroveError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("templates"))
+        .map_err(GroveError::internal)
+}
+
+pub struct TemplateVariables {
+    pub game_name: String,
+    pub game_slug: String,
+    pub author: String,
+    pub date: String, // YYYY-MM-DD
+    pub color: String,
+}
+
+impl TemplateVariables {
+    fn pairs(&self) -> [(&'static str, &str); 5] {
+        [
+            ("game_name", &self.game_name),
+            ("game_slug", &self.game_slug),
+            ("author", &self.author),
+            ("date", &self.date),
+            ("color", &self.color),
+        ]
+    }
+}
+
+// Replaces {{variable}} placeholders, escaping values that end up in HTML
+pub fn render_template(contents: &str, variables: &TemplateVariables, escape_html: bool) -> String {
+    let mut rendered = contents.to_string();
+    for (key, value) in variables.pairs() {
+        let value = if escape_html { html_escape(value) } else { value.to_string() };
+        rendered = rendered.replace(&format!("{{{{{}}}}}", key), &value);
+    }
+    rendered
+}
+
+pub fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn is_html_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("html") | Some("htm")
+    )
+}
+
+fn render_file(source: &Path, target: &Path, variables: &TemplateVariables) -> Result<(), GroveError> {
+    let contents = fs::read_to_string(source)
+        .map_err(|e| GroveError::io("read_template_file", e))?;
+
+    let rendered = render_template(&contents, variables, is_html_file(target));
+
+    fs::write(target, rendered)
+        .map_err(|e| GroveError::io("write_template_file", e))
+}
+
+// Fills in template variables in a freshly copied game folder
+pub fn render_game_files(game_path: &Path, template: &TemplateEntry, variables: &TemplateVariables) -> Result<(), GroveError> {
+    for relative_path in &template.render {
+        let path = game_path.join(relative_path);
+        if path.is_file() {
+            render_file(&path, &path, variables)?;
+        }
+    }
+
+    render_tmpl_files(game_path, variables)
+}
+
+fn render_tmpl_files(dir: &Path, variables: &TemplateVariables) -> Result<(), GroveError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| GroveError::io("read_template_folder", e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            render_tmpl_files(&path, variables)?;
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some(TEMPLATE_FILE_EXTENSION) {
+            // index.html.tmpl becomes index.html
+            let target = path.with_extension("");
+            render_file(&path, &target, variables)?;
+            fs::remove_file(&path)
+                .map_err(|e| GroveError::io("remove_template_file", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Formats a Unix timestamp as YYYY-MM-DD (UTC) without pulling in a date library
+pub fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+
+    // Civil-from-days conversion, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// Directories that may contain templates, in order of precedence
+fn template_roots(app_handle: &tauri::AppHandle) -> Vec<(PathBuf, TemplateSource)> {
+    let mut roots = Vec::new();
+
+    for dir in files::bundled_dirs(app_handle, "templates") {
+        roots.push((dir, TemplateSource::Bundled));
+    }
+
+    // Templates saved by the family come last so they can't shadow built-ins
+    if let Ok(user_dir) = user_templates_dir(app_handle) {
+        roots.push((user_dir, TemplateSource::User));
+    }
+
+    roots
+}
+
+pub fn read_template_manifest(template_path: &Path) -> Option<TemplateManifest> {
+    let contents = fs::read_to_string(template_path.join(TEMPLATE_MANIFEST_FILE_NAME)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn template_entry(path: &Path, source: TemplateSource) -> Option<TemplateEntry> {
+    if !path.is_dir() {
+        return None;
+    }
+
+    let id = path.file_name()?.to_str()?.to_string();
+    let manifest = read_template_manifest(path)?;
+
+    let preview = manifest
+        .preview
+        .map(|preview| path.join(preview))
+        .filter(|preview| preview.is_file())
+        .map(|preview| preview.to_string_lossy().to_string());
+
+    Some(TemplateEntry {
+        id,
+        name: manifest.name,
+        description: manifest.description,
+        preview,
+        tags: manifest.tags,
+        game_type: manifest.game_type,
+        version: manifest.version,
+        render: manifest.render,
+        source,
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+pub fn list_templates(app_handle: &tauri::AppHandle) -> Vec<TemplateEntry> {
+    let mut templates = Vec::new();
+    let mut seen_ids = HashSet::new();
+
+    for (root, source) in template_roots(app_handle) {
+        let entries = match fs::read_dir(&root) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut found = Vec::new();
+        for entry in entries.flatten() {
+            if let Some(template) = template_entry(&entry.path(), source) {
+                found.push(template);
+            }
+        }
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // The same bundled folder can be reachable through several roots
+        for template in found {
+            if seen_ids.insert(template.id.clone()) {
+                templates.push(template);
+            }
+        }
+    }
+
+    templates
+}
+
+pub fn find_template(app_handle: &tauri::AppHandle, template_id: &str) -> Result<TemplateEntry, GroveError> {
+    list_templates(app_handle)
+        .into_iter()
+        .find(|template| template.id == template_id)
+        .ok_or_else(|| GroveError::TemplateMissing { template_id: template_id.to_string() })
+}
+
+// Copies a game into the user template directory so it can be used as a
+// starting point for new games.
+pub fn save_as_template(app_handle: &tauri::AppHandle, game_path: &Path, template_name: &str) -> Result<TemplateEntry, GroveError> {
+    let template_name = validation::validate_display_name(template_name)?;
+    let template_name = template_name.as_str();
+    let template_id = validation::sanitize_folder_name(template_name)?;
+
+    let user_dir = user_templates_dir(app_handle)?;
+    fs::create_dir_all(&user_dir)
+        .map_err(|e| GroveError::io("create_templates_folder", e))?;
+
+    // Built-in and saved templates share one namespace
+    if list_templates(app_handle).iter().any(|template| template.id == template_id) {
+        return Err(GroveError::AlreadyExists { name: template_name.to_string() });
+    }
+
+    let template_path = user_dir.join(&template_id);
+    if template_path.exists() {
+        return Err(GroveError::AlreadyExists { name: template_name.to_string() });
+    }
+
+    fs::create_dir(&template_path)
+        .map_err(|e| GroveError::io("create_template_folder", e))?;
+
+    if let Err(e) = copy_into_template(game_path, &template_path, template_name) {
+        // Don't leave a half-copied template behind
+        let _ = fs::remove_dir_all(&template_path);
+        return Err(e);
+    }
+
+    template_entry(&template_path, TemplateSource::User)
+        .ok_or_else(|| GroveError::TemplateMissing { template_id: template_name.to_string() })
+}
+
+fn copy_into_template(game_path: &Path, template_path: &Path, template_name: &str) -> Result<(), GroveError> {
+    files::copy_game_contents(game_path, template_path)
+        .map_err(|e| GroveError::io("copy_game_files", e))?;
+
+    // New games get their own manifest when they're created
+    let game_manifest = manifest::read_manifest(game_path);
+    let _ = fs::remove_file(template_path.join(manifest::MANIFEST_FILE_NAME));
+
+    // Let the page title follow the name of each new game
+    let mut render = Vec::new();
+    let index_path = template_path.join("index.html");
+    if let Ok(html) = fs::read_to_string(&index_path) {
+        if let Some(templated) = template_title(&html) {
+            fs::write(&index_path, templated)
+                .map_err(|e| GroveError::io("write_template_file", e))?;
+            render.push("index.html".to_string());
+        }
+    }
+
+    let description = match &game_manifest {
+        Some(game) => format!("Made from {}", game.name),
+        None => String::new(),
+    };
+
+    let template_manifest = TemplateManifest {
+        name: template_name.to_string(),
+        description,
+        preview: None,
+        tags: vec!["custom".to_string()],
+        game_type: game_manifest
+            .map(|game| game.game_type)
+            .unwrap_or_else(default_game_type),
+        version: Some("1.0.0".to_string()),
+        render,
+    };
+
+    let contents = serde_json::to_string_pretty(&template_manifest)
+        .map_err(GroveError::internal)?;
+
+    fs::write(template_path.join(TEMPLATE_MANIFEST_FILE_NAME), contents)
+        .map_err(|e| GroveError::io("write_template_manifest", e))
+}
+
+fn template_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title>")? + "<title>".len();
+    let end = start + lower[start..].find("</title>")?;
+
+    Some(format!("{}{{{{game_name}}}}{}", &html[..start], &html[end..]))
+}
+
+// Swaps the page title for a new game name, but only when the title is still
+// the old name so a title the kid wrote by hand is left alone.
+pub fn retitle_page(game_path: &Path, old_name: &str, new_name: &str) -> Result<(), GroveError> {
+    let index_path = game_path.join("index.html");
+    let html = match fs::read_to_string(&index_path) {
+        Ok(html) => html,
+        Err(_) => return Ok(()),
+    };
+
+    let old_title = format!("<title>{}</title>", html_escape(old_name));
+    if !html.contains(&old_title) {
+        return Ok(());
+    }
+
+    let new_title = format!("<title>{}</title>", html_escape(new_name));
+    fs::write(&index_path, html.replacen(&old_title, &new_title, 1))
+        .map_err(|e| GroveError::io("update_page_title", e))
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::GroveError;
+
+// Per-game files the app manages itself live in a hidden .grove folder,
+// which snapshots, copies and file watchers all leave alone.
+pub const GROVE_DIR_NAME: &str = ".grove";
+
+const THUMBNAIL_FILE_NAME: &str = "thumbnail.png";
+
+// Anything bigger is not a thumbnail
+pub const MAX_THUMBNAIL_SIZE: usize = 2 * 1024 * 1024;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+pub fn thumbnail_path(game_path: &Path) -> PathBuf {
+    game_path.join(GROVE_DIR_NAME).join(THUMBNAIL_FILE_NAME)
+}
+
+pub fn existing_thumbnail(game_path: &Path) -> Option<String> {
+    let path = thumbnail_path(game_path);
+    if path.is_file() {
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    }
+}
+
+pub fn save_thumbnail(game_path: &Path, png: &[u8]) -> Result<(), GroveError> {
+    if png.len() > MAX_THUMBNAIL_SIZE || !png.starts_with(PNG_SIGNATURE) {
+        return Err(GroveError::Internal { details: "Thumbnail is not a PNG image".to_string() });
+    }
+
+    let path = thumbnail_path(game_path);
+    if let Some(grove_dir) = path.parent() {
+        fs::create_dir_all(grove_dir)
+            .map_err(|e| GroveError::io("create_grove_folder", e))?;
+    }
+
+    fs::write(&path, png)
+        .map_err(|e| GroveError::io("save_thumbnail", e))
+}
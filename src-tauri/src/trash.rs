@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::error::GroveError;
+use crate::files::MoveError;
+use crate::{files, manifest, validation};
+
+// Deleted games are moved into the app's own trash folder instead of being
+// removed, each one next to a record of where it came from.
+const TRASH_RECORD_FILE_NAME: &str = "trash.json";
+const TRASHED_GAME_DIR_NAME: &str = "game";
+
+// Games older than this are purged for good
+pub const TRASH_RETENTION_DAYS: u64 = 30;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TrashEntry {
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    pub deleted_at: u64, // Unix timestamp
+}
+
+fn trash_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, GroveError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("trash"))
+        .map_err(GroveError::internal)
+}
+
+pub fn trash_game(app_handle: &tauri::AppHandle, game_path: &Path) -> Result<TrashEntry, GroveError> {
+    let folder_name = game_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| GroveError::not_a_directory(game_path))?;
+
+    let name = manifest::read_manifest(game_path)
+        .map(|manifest| manifest.name)
+        .unwrap_or(folder_name);
+
+    let deleted_at = manifest::now_unix();
+    let trash_root = trash_dir(app_handle)?;
+    fs::create_dir_all(&trash_root)
+        .map_err(|e| GroveError::io("create_trash_folder", e))?;
+
+    // Timestamps keep ids sortable, the counter handles several per second
+    let id = files::unique_folder_name(&trash_root, &deleted_at.to_string());
+    let entry_dir = trash_root.join(&id);
+    fs::create_dir(&entry_dir)
+        .map_err(|e| GroveError::io("create_trash_folder", e))?;
+
+    let entry = TrashEntry {
+        id,
+        name,
+        original_path: game_path.to_string_lossy().to_string(),
+        deleted_at,
+    };
+
+    // Write the record first so a moved game is never left without one
+    let record = serde_json::to_string_pretty(&entry)
+        .map_err(GroveError::internal)?;
+    fs::write(entry_dir.join(TRASH_RECORD_FILE_NAME), record)
+        .map_err(|e| GroveError::io("write_trash_record", e))?;
+
+    match files::move_dir(game_path, &entry_dir.join(TRASHED_GAME_DIR_NAME)) {
+        Ok(()) => Ok(entry),
+        // Nothing reached the trash, the game is still where it was
+        Err(MoveError::Copy(e)) => {
+            let _ = fs::remove_dir_all(&entry_dir);
+            Err(GroveError::io("move_to_trash", e))
+        }
+        // The trash holds the only whole copy now, so it stays and can be
+        // restored like any other trashed game
+        Err(MoveError::RemoveSource(e)) => Err(GroveError::io("remove_trashed_game", e)),
+    }
+}
+
+fn read_trash_entry(entry_dir: &Path) -> Option<TrashEntry> {
+    let contents = fs::read_to_string(entry_dir.join(TRASH_RECORD_FILE_NAME)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+// Every trashed game, whichever games root it came from
+fn all_trash(app_handle: &tauri::AppHandle) -> Result<Vec<TrashEntry>, GroveError> {
+    let trash_root = trash_dir(app_handle)?;
+    let entries = match fs::read_dir(&trash_root) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()), // Nothing was ever trashed
+    };
+
+    let mut trashed = Vec::new();
+    for entry in entries.flatten() {
+        if let Some(trash_entry) = read_trash_entry(&entry.path()) {
+            trashed.push(trash_entry);
+        }
+    }
+
+    Ok(trashed)
+}
+
+// Newest first, only games deleted from a root the current player can see
+pub fn list_trash(app_handle: &tauri::AppHandle) -> Result<Vec<TrashEntry>, GroveError> {
+    let mut trashed: Vec<TrashEntry> = all_trash(app_handle)?
+        .into_iter()
+        .filter(|entry| {
+            validation::ensure_trashed_game_in_games_root(app_handle, Path::new(&entry.original_path)).is_ok()
+        })
+        .collect();
+
+    trashed.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+    Ok(trashed)
+}
+
+// Puts a game back where it was. If something else took its place in the
+// meantime the game comes back as "Name (2)" instead.
+pub fn restore_from_trash(app_handle: &tauri::AppHandle, id: &str) -> Result<String, GroveError> {
+    let trash_root = trash_dir(app_handle)?;
+    let entry_dir = trash_root.join(id);
+
+    // Ids come from the webview, make sure this one is really inside the trash
+    if entry_dir.parent() != Some(trash_root.as_path()) {
+        return Err(GroveError::NotInTrash { id: id.to_string() });
+    }
+
+    let entry = read_trash_entry(&entry_dir)
+        .ok_or_else(|| GroveError::NotInTrash { id: id.to_string() })?;
+
+    let original_path = PathBuf::from(&entry.original_path);
+
+    // A child can only bring back games from their own folder
+    validation::ensure_trashed_game_in_games_root(app_handle, &original_path)?;
+
+    let parent = original_path.parent()
+        .ok_or_else(|| GroveError::not_found(&original_path))?;
+    let folder_name = original_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| GroveError::not_a_directory(&original_path))?;
+
+    fs::create_dir_all(parent)
+        .map_err(|e| GroveError::io("create_folder", e))?;
+    let restored_path = parent.join(files::unique_folder_name(parent, &folder_name));
+
+    match files::move_dir(&entry_dir.join(TRASHED_GAME_DIR_NAME), &restored_path) {
+        Ok(()) => {}
+        Err(MoveError::Copy(e)) => return Err(GroveError::io("restore_from_trash", e)),
+        // The game is back in one piece, only the trash copy is left over
+        Err(MoveError::RemoveSource(e)) => println!("Failed to clear restored game from the trash: {}", e),
+    }
+    let _ = fs::remove_dir_all(&entry_dir);
+
+    Ok(restored_path.to_string_lossy().to_string())
+}
+
+pub fn empty_trash(app_handle: &tauri::AppHandle) -> Result<(), GroveError> {
+    let trash_root = trash_dir(app_handle)?;
+    if !trash_root.exists() {
+        return Ok(());
+    }
+
+    fs::remove_dir_all(&trash_root)
+        .map_err(|e| GroveError::io("empty_trash", e))
+}
+
+// Removes games that have been in the trash longer than the retention period
+pub fn purge_old_trash(app_handle: &tauri::AppHandle) -> Result<(), GroveError> {
+    let trash_root = trash_dir(app_handle)?;
+    let cutoff = manifest::now_unix().saturating_sub(TRASH_RETENTION_DAYS * 24 * 60 * 60);
+
+    for entry in all_trash(app_handle)? {
+        if entry.deleted_at < cutoff {
+            fs::remove_dir_all(trash_root.join(&entry.id))
+                .map_err(|e| GroveError::io("purge_trash", e))?;
+        }
+    }
+
+    Ok(())
+}
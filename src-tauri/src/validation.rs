@@ -0,0 +1,285 @@
+use std::path::{Component, Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::{GroveError, NameProblem};
+use crate::settings;
+
+// Names typed by kids end up as folder names and paths come straight from the
+// webview, so everything that touches the filesystem goes through here first.
+
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 60;
+
+// Most filesystems stop at 255 bytes per name
+const MAX_FOLDER_NAME_BYTES: usize = 255;
+
+// Not allowed in file names on Windows, and / is never allowed anywhere
+const RESERVED_CHARACTERS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+// Names Windows refuses to create, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul",
+    "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9",
+    "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+// Checks a display name and returns it trimmed and in a single Unicode form,
+// so "é" typed on different keyboards always compares equal
+pub fn validate_display_name(name: &str) -> Result<String, GroveError> {
+    let name: String = name.nfc().collect();
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(GroveError::invalid_name(name, NameProblem::Empty, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    if name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+        return Err(GroveError::invalid_name(name, NameProblem::TooLong, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    if name.chars().any(|c| c.is_control()) {
+        return Err(GroveError::invalid_name(name, NameProblem::InvisibleCharacters, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    Ok(name.to_string())
+}
+
+// Cleans a name that's used as a folder name as is. Reserved characters are
+// dropped, and anything that could point outside the parent folder (like
+// "..") is refused.
+pub fn sanitize_folder_name(name: &str) -> Result<String, GroveError> {
+    let name: String = name
+        .nfc()
+        .filter(|c| !c.is_control() && !RESERVED_CHARACTERS.contains(c))
+        .collect();
+
+    // Windows silently drops trailing dots and spaces, which makes folders
+    // that can't be opened again
+    let name = name.trim().trim_end_matches(['.', ' ']);
+
+    if name.is_empty() {
+        return Err(GroveError::invalid_name(name, NameProblem::Empty, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    if name == "." || name == ".." || name.starts_with('.') {
+        return Err(GroveError::invalid_name(name, NameProblem::Reserved, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    let stem = name.split('.').next().unwrap_or(name).to_lowercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        return Err(GroveError::invalid_name(name, NameProblem::Reserved, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    if name.chars().count() > MAX_DISPLAY_NAME_LENGTH || name.len() > MAX_FOLDER_NAME_BYTES {
+        return Err(GroveError::invalid_name(name, NameProblem::TooLong, MAX_DISPLAY_NAME_LENGTH));
+    }
+
+    // Belt and braces: after cleaning this has to be a single plain component
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(name.to_string()),
+        _ => Err(GroveError::invalid_name(name, NameProblem::Reserved, MAX_DISPLAY_NAME_LENGTH)),
+    }
+}
+
+fn canonical_games_roots(app_handle: &tauri::AppHandle) -> Result<Vec<PathBuf>, GroveError> {
+    let roots: Vec<PathBuf> = settings::games_roots(app_handle)
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect();
+
+    if roots.is_empty() {
+        return Err(GroveError::NoGamesRoot);
+    }
+
+    Ok(roots)
+}
+
+// Resolves symlinks and ".." so a path can't sneak out of its games root
+fn canonical_path(path: &Path) -> Result<PathBuf, GroveError> {
+    path.canonicalize().map_err(|_| GroveError::not_found(path))
+}
+
+// For commands that work on a whole library: the path has to be a games root
+// itself, so a new game can never end up inside another game
+pub fn ensure_games_root(app_handle: &tauri::AppHandle, path: &Path) -> Result<(), GroveError> {
+    check_is_games_root(&canonical_games_roots(app_handle)?, &canonical_path(path)?)
+}
+
+// For commands that work on a single game: strictly inside a games root, so
+// a root itself can never be renamed, trashed or overwritten
+pub fn ensure_game_in_games_root(app_handle: &tauri::AppHandle, path: &Path) -> Result<(), GroveError> {
+    check_is_inside_games_root(&canonical_games_roots(app_handle)?, &canonical_path(path)?)
+}
+
+// For games in the trash: they no longer exist, so the folder they were
+// deleted from has to be inside a games root instead
+pub fn ensure_trashed_game_in_games_root(app_handle: &tauri::AppHandle, original_path: &Path) -> Result<(), GroveError> {
+    let parent = original_path.parent()
+        .and_then(|parent| parent.canonicalize().ok())
+        .ok_or_else(|| outside_games_root(original_path))?;
+
+    if canonical_games_roots(app_handle)?.iter().any(|root| parent.starts_with(root)) {
+        Ok(())
+    } else {
+        Err(outside_games_root(original_path))
+    }
+}
+
+// Both paths are canonical
+fn check_is_games_root(roots: &[PathBuf], path: &Path) -> Result<(), GroveError> {
+    if roots.iter().any(|root| root == path) {
+        Ok(())
+    } else {
+        Err(outside_games_root(path))
+    }
+}
+
+fn check_is_inside_games_root(roots: &[PathBuf], path: &Path) -> Result<(), GroveError> {
+    if roots.iter().any(|root| path.starts_with(root) && path != root) {
+        Ok(())
+    } else {
+        Err(outside_games_root(path))
+    }
+}
+
+fn outside_games_root(path: &Path) -> GroveError {
+    GroveError::OutsideGamesRoot { path: path.to_string_lossy().to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    // A fresh folder for each test, removed again when the test is done
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("grove-validation-test-{}", crate::manifest::new_game_id()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir.canonicalize().unwrap())
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn is_reserved(result: Result<String, GroveError>) -> bool {
+        matches!(result, Err(GroveError::InvalidName { reason: NameProblem::Reserved, .. }))
+    }
+
+    #[test]
+    fn refuses_names_that_leave_the_folder() {
+        assert!(sanitize_folder_name("..").is_err());
+        assert!(sanitize_folder_name(".").is_err());
+        // Separators are dropped, what's left can't climb out either
+        assert!(sanitize_folder_name("../..").is_err());
+        assert!(sanitize_folder_name("..\\..").is_err());
+        assert!(is_reserved(sanitize_folder_name("../secrets")));
+        assert!(is_reserved(sanitize_folder_name(".hidden")));
+    }
+
+    #[test]
+    fn drops_separators_and_reserved_characters() {
+        assert_eq!(sanitize_folder_name("space/cat").unwrap(), "spacecat");
+        assert_eq!(sanitize_folder_name("space\\cat").unwrap(), "spacecat");
+        assert_eq!(sanitize_folder_name("what? <cat>: \"yes\" | no*").unwrap(), "what cat yes  no");
+    }
+
+    #[test]
+    fn refuses_windows_reserved_names() {
+        assert!(is_reserved(sanitize_folder_name("con")));
+        assert!(is_reserved(sanitize_folder_name("NUL")));
+        assert!(is_reserved(sanitize_folder_name("com1.txt")));
+        assert!(is_reserved(sanitize_folder_name("lpt9")));
+        assert_eq!(sanitize_folder_name("console").unwrap(), "console");
+    }
+
+    #[test]
+    fn trims_trailing_dots_and_spaces() {
+        assert_eq!(sanitize_folder_name("  space cat. . ").unwrap(), "space cat");
+        assert_eq!(sanitize_folder_name("v1.0...").unwrap(), "v1.0");
+        assert!(matches!(
+            sanitize_folder_name(" . . "),
+            Err(GroveError::InvalidName { reason: NameProblem::Empty, .. })
+        ));
+    }
+
+    #[test]
+    fn drops_control_characters() {
+        assert_eq!(sanitize_folder_name("space\u{0}cat\n\u{7f}").unwrap(), "spacecat");
+        assert!(matches!(
+            sanitize_folder_name("\u{1b}\t"),
+            Err(GroveError::InvalidName { reason: NameProblem::Empty, .. })
+        ));
+    }
+
+    #[test]
+    fn normalizes_to_nfc() {
+        // "é" as one code point and as "e" plus a combining accent
+        let composed = sanitize_folder_name("caf\u{e9}").unwrap();
+        let decomposed = sanitize_folder_name("cafe\u{301}").unwrap();
+        assert_eq!(composed, decomposed);
+        assert_eq!(composed, "caf\u{e9}");
+    }
+
+    #[test]
+    fn only_a_root_itself_is_a_games_root() {
+        let temp = TempDir::new();
+        let root = temp.0.join("games");
+        let game = root.join("space-cat");
+        fs::create_dir_all(&game).unwrap();
+        let roots = [root.clone()];
+
+        assert!(check_is_games_root(&roots, &root).is_ok());
+        assert!(check_is_games_root(&roots, &game).is_err());
+        assert!(check_is_games_root(&roots, &temp.0).is_err());
+    }
+
+    #[test]
+    fn games_are_strictly_inside_a_root() {
+        let temp = TempDir::new();
+        let root = temp.0.join("games");
+        let game = root.join("space-cat");
+        let other = temp.0.join("games-old").join("space-cat");
+        fs::create_dir_all(&game).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let roots = [root.clone()];
+
+        assert!(check_is_inside_games_root(&roots, &game).is_ok());
+        assert!(check_is_inside_games_root(&roots, &root).is_err());
+        // Shares the root's name as a prefix, but not its folder
+        assert!(check_is_inside_games_root(&roots, &other).is_err());
+        assert!(check_is_inside_games_root(&roots, &temp.0).is_err());
+    }
+
+    #[test]
+    fn dot_dot_is_resolved_before_checking() {
+        let temp = TempDir::new();
+        let root = temp.0.join("games");
+        fs::create_dir_all(root.join("space-cat")).unwrap();
+        fs::create_dir_all(temp.0.join("secrets")).unwrap();
+        let roots = [root.clone()];
+
+        let escaping = canonical_path(&root.join("space-cat").join("..").join("..").join("secrets")).unwrap();
+        assert!(check_is_inside_games_root(&roots, &escaping).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_a_root_are_resolved_before_checking() {
+        let temp = TempDir::new();
+        let root = temp.0.join("games");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(temp.0.join("secrets")).unwrap();
+        std::os::unix::fs::symlink(temp.0.join("secrets"), root.join("sneaky")).unwrap();
+        let roots = [root.clone()];
+
+        let linked = canonical_path(&root.join("sneaky")).unwrap();
+        assert!(check_is_inside_games_root(&roots, &linked).is_err());
+    }
+}
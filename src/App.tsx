@@ -1,14 +1,71 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { Store } from "@tauri-apps/plugin-store";
 import { check } from "@tauri-apps/plugin-updater";
 import "./App.css";
 
+interface GameManifest {
+  id: string; // Survives renames, copies get a new one
+  name: string;
+  icon: string | null;
+  game_type: string;
+  template: { name: string; version: string };
+  created_at: number; // Unix timestamp
+  author: string | null;
+  remixed_from: { id: string; name: string } | null; // Parent game's manifest id
+}
+
 interface GameEntry {
   name: string;
   path: string;
   last_modified: number; // Unix timestamp
+  manifest: GameManifest | null; // Missing for games created before game.json existed
+  thumbnail: string | null; // File path, load it with convertFileSrc
+  root: string | null; // Path of the library root the game lives in
+}
+
+interface LibraryRoot {
+  path: string;
+  label: string;
+  color: string;
+}
+
+// A game shared with phones and tablets on the same Wi-Fi
+interface LanShareInfo {
+  game_path: string;
+  url: string;
+  port: number;
+  qr_code: string; // PNG data URI
+  seconds_left: number; // Sharing stops by itself after this
+}
+
+// Every command rejects with one of these, `kind` says what went wrong
+interface GroveError {
+  kind: string;
+  message_key: string;
+  message: string;
+}
+
+// Runs a grown-up action, asking for the parent PIN once if it's needed
+async function withParentPin<T>(action: () => Promise<T>): Promise<T> {
+  try {
+    return await action();
+  } catch (err) {
+    if ((err as GroveError)?.kind !== "parent_pin_required") throw err;
+    const pin = window.prompt("Ask a grown-up to type their PIN");
+    if (pin === null) throw err;
+    await invoke("verify_parent_pin", { pin });
+    return await action();
+  }
+}
+
+function errorMessage(err: unknown, fallback: string): string {
+  if (err instanceof Error) return err.message;
+  if (typeof err === "object" && err !== null && "message" in err) {
+    return (err as GroveError).message;
+  }
+  return fallback;
 }
 
 function App() {
@@ -28,6 +85,7 @@ function App() {
   const [updateInfo, setUpdateInfo] = useState<any>(null);
   const [checkingUpdate, setCheckingUpdate] = useState(false);
   const [downloading, setDownloading] = useState(false);
+  const [lanShare, setLanShare] = useState<LanShareInfo | null>(null);
 
   // Initialize store and load saved path on mount
   useEffect(() => {
@@ -44,6 +102,14 @@ function App() {
         const savedPath = await appStore.get<string>("selected_games_path");
         console.log("Loaded saved path from store:", savedPath);
         if (savedPath) {
+          // Folders picked before there were several roots only live in the
+          // store, the backend needs them added as a root to allow access
+          const roots = await invoke<LibraryRoot[]>("list_library_roots");
+          if (!roots.some((root) => root.path === savedPath)) {
+            const label = savedPath.split(/[\\/]/).filter(Boolean).pop() ?? savedPath;
+            await invoke<LibraryRoot>("add_library_root", { path: savedPath, label })
+              .catch((err) => console.error("Failed to add saved games folder as a root:", err));
+          }
           setSelectedPath(savedPath);
         }
       } catch (err) {
@@ -88,23 +154,8 @@ function App() {
       setDownloading(true);
       console.log("Downloading and installing update...");
 
-      // Download and install the update
-      await updateInfo.downloadAndInstall((event: any) => {
-        switch (event.event) {
-          case "Started":
-            console.log("Update download started");
-            break;
-          case "Progress":
-            console.log(`Download progress: ${event.data.chunkLength} bytes`);
-            break;
-          case "Finished":
-            console.log("Update download finished");
-            break;
-        }
-      });
-
-      // Restart the app to apply the update
-      console.log("Restarting application...");
+      // Installing needs a grown-up, the backend restarts the app when done
+      await withParentPin(() => invoke("install_update"));
     } catch (error) {
       console.error("Failed to install update:", error);
       setDownloading(false);
@@ -129,7 +180,7 @@ function App() {
         setGames(sortedGames);
       } catch (err) {
         console.error(err);
-        setError(err instanceof Error ? err.message : "Failed to load games");
+        setError(errorMessage(err, "Failed to load games"));
         setGames([]);
       } finally {
         setLoading(false);
@@ -139,6 +190,21 @@ function App() {
     loadGames();
   }, [selectedPath]);
 
+  // Sharing times out on its own, so check in on it while it's shown
+  useEffect(() => {
+    if (!lanShare) return;
+
+    const interval = setInterval(async () => {
+      try {
+        setLanShare(await invoke<LanShareInfo | null>("get_lan_share"));
+      } catch (err) {
+        console.error("Error checking LAN share:", err);
+      }
+    }, 15000);
+
+    return () => clearInterval(interval);
+  }, [lanShare !== null]);
+
   // Save selected path to store whenever it changes
   useEffect(() => {
     if (!store || initializing) return;
@@ -167,6 +233,16 @@ function App() {
       });
 
       if (selected && typeof selected === "string") {
+        // The backend only allows access inside library roots, so add it
+        // before it is selected. Picking a root again is fine.
+        const label = selected.split(/[\\/]/).filter(Boolean).pop() ?? selected;
+        await withParentPin(() =>
+          invoke<LibraryRoot>("add_library_root", { path: selected, label }),
+        ).catch(
+          (err) => {
+            if (err?.kind !== "already_exists") throw err;
+          },
+        );
         setSelectedPath(selected);
       }
     } catch (err) {
@@ -203,7 +279,8 @@ function App() {
       await invoke("create_game_folder", {
         parentPath: selectedPath,
         folderName: formattedName,
-        gameType: gameType,
+        templateId: gameType,
+        options: { display_name: newGameName.trim() },
       });
 
       // Reload games list
@@ -224,7 +301,7 @@ function App() {
     } catch (err) {
       console.error("Error creating game folder:", err);
       setError(
-        err instanceof Error ? err.message : "Failed to create game folder",
+        errorMessage(err, "Failed to create game folder"),
       );
     } finally {
       setCreating(false);
@@ -247,12 +324,13 @@ function App() {
     setError(null);
   }
 
-  async function openInCursor(gamePath: string) {
+  async function openInEditor(gamePath: string) {
     try {
-      await invoke("open_in_cursor", { folderPath: gamePath });
+      // Uses the player's preferred editor, or the first one installed
+      await invoke("open_in_editor", { path: gamePath });
     } catch (err) {
-      console.error("Error opening in Cursor:", err);
-      setError(err instanceof Error ? err.message : "Failed to open in Cursor");
+      console.error("Error opening in editor:", err);
+      setError(errorMessage(err, "Failed to open in editor"));
     }
   }
 
@@ -262,8 +340,90 @@ function App() {
     } catch (err) {
       console.error("Error opening in browser:", err);
       setError(
-        err instanceof Error ? err.message : "Failed to open in browser",
+        errorMessage(err, "Failed to open in browser"),
+      );
+    }
+  }
+
+  async function exportGame(game: GameEntry) {
+    try {
+      const outPath = await save({
+        title: "Save your game as one file",
+        defaultPath: `${game.manifest?.name ?? game.name}.html`,
+        filters: [{ name: "Web page", extensions: ["html"] }],
+      });
+      if (!outPath) return;
+
+      await invoke("export_single_html", { gamePath: game.path, outPath });
+    } catch (err) {
+      console.error("Error exporting game:", err);
+      setError(errorMessage(err, "Failed to export game"));
+    }
+  }
+
+  async function exportGameZip(game: GameEntry) {
+    try {
+      const outPath = await save({
+        title: "Save your game as a zip",
+        defaultPath: `${game.name}.zip`,
+        filters: [{ name: "Zip file", extensions: ["zip"] }],
+      });
+      if (!outPath) return;
+
+      await invoke("export_game_zip", { gamePath: game.path, outPath });
+    } catch (err) {
+      console.error("Error exporting game zip:", err);
+      setError(errorMessage(err, "Failed to export game"));
+    }
+  }
+
+  async function importGameZip() {
+    if (!selectedPath) return;
+
+    try {
+      const zipPath = await open({
+        title: "Pick a game zip",
+        multiple: false,
+        filters: [{ name: "Zip file", extensions: ["zip"] }],
+      });
+      if (!zipPath) return;
+
+      await invoke("import_game_zip", { zipPath, libraryRoot: selectedPath });
+
+      // Reload games list
+      const result = await invoke<GameEntry[]>("read_folders_from_path", {
+        folderPath: selectedPath,
+      });
+      // Sort by last modified (newest first)
+      const sortedGames = result.sort(
+        (a, b) => b.last_modified - a.last_modified,
       );
+      setGames(sortedGames);
+    } catch (err) {
+      console.error("Error importing game:", err);
+      setError(errorMessage(err, "Failed to import game"));
+    }
+  }
+
+  async function startLanShare(game: GameEntry) {
+    try {
+      const info = await withParentPin(() =>
+        invoke<LanShareInfo>("start_lan_share", { gamePath: game.path }),
+      );
+      setLanShare(info);
+    } catch (err) {
+      console.error("Error sharing game:", err);
+      setError(errorMessage(err, "Failed to share game"));
+    }
+  }
+
+  async function stopLanShare() {
+    try {
+      await invoke("stop_lan_share");
+    } catch (err) {
+      console.error("Error stopping LAN share:", err);
+    } finally {
+      setLanShare(null);
     }
   }
 
@@ -297,6 +457,13 @@ function App() {
           >
             ➕ New Game
           </button>
+          <button
+            onClick={importGameZip}
+            className="create-button import-button"
+            title="Add a game from a zip file"
+          >
+            📥 Import
+          </button>
         </>
       )}
 
@@ -452,11 +619,35 @@ function App() {
                   </button>
                   <button
                     className="action-button cursor-button"
-                    onClick={() => openInCursor(selectedGame.path)}
-                    title="Open in Cursor"
+                    onClick={() => openInEditor(selectedGame.path)}
+                    title="Open in your code editor"
                   >
                     <span className="button-icon">💻</span>
-                    Cursor
+                    Code
+                  </button>
+                  <button
+                    className="action-button export-button"
+                    onClick={() => exportGame(selectedGame)}
+                    title="Save as one file you can send to anyone"
+                  >
+                    <span className="button-icon">📦</span>
+                    Share
+                  </button>
+                  <button
+                    className="action-button export-button"
+                    onClick={() => exportGameZip(selectedGame)}
+                    title="Save as a zip another computer can import"
+                  >
+                    <span className="button-icon">🗜️</span>
+                    Zip
+                  </button>
+                  <button
+                    className="action-button export-button"
+                    onClick={() => startLanShare(selectedGame)}
+                    title="Play on a phone or tablet on the same Wi-Fi"
+                  >
+                    <span className="button-icon">📱</span>
+                    Wi-Fi
                   </button>
                 </div>
               </div>
@@ -577,6 +768,29 @@ function App() {
           </div>
         </div>
       )}
+
+      {/* LAN Share Modal */}
+      {lanShare && (
+        <div className="modal-overlay">
+          <div className="modal share-modal">
+            <h2>📱 Play on Another Device</h2>
+            <p className="modal-description">
+              Scan this with a phone or tablet on the same Wi-Fi
+            </p>
+            <img className="share-qr" src={lanShare.qr_code} alt="QR code" />
+            <p className="share-url">{lanShare.url}</p>
+            <p className="hint">
+              Sharing stops by itself in{" "}
+              {Math.max(1, Math.ceil(lanShare.seconds_left / 60))} minutes
+            </p>
+            <div className="modal-buttons">
+              <button onClick={stopLanShare} className="confirm-button">
+                Stop Sharing
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
     </main>
   );
 }